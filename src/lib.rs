@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::{parse_macro_input, Attribute, DeriveInput, Expr, Lit, Meta};
 
 
@@ -12,13 +13,793 @@ struct AutoFieldConfig {
     pub tenant: bool,
     pub version: bool,
     pub soft_delete: bool,
+    /// 输出受管列 DDL 片段的数据库方言
+    pub emit_ddl: Option<SqlDialect>,
+    /// 输出生成查询方法的推荐索引
+    pub emit_index_hints: bool,
+    /// 时间戳的取值来源
+    pub timestamps_source: TimestampSource,
+    /// 自定义时钟函数，签名为 fn() -> chrono::DateTime<chrono::Utc>
+    pub clock_fn: Option<syn::Path>,
+    /// 时间戳截断精度，需与数据库列精度一致
+    pub timestamp_precision: Option<TimestampPrecision>,
+    /// 过期时长（秒），配置后插入时填充 expire_time = now + ttl
+    pub ttl_seconds: Option<i64>,
+    /// 软删除数据的保留期（秒），#[auto_field(retention = "90d")]，超过保留期的记录由 enforce_retention 物理删除
+    pub retention_seconds: Option<i64>,
+    /// 排序列名，默认为 sort
+    pub sort_column: Option<String>,
+    /// 排序列的插入填充方式
+    pub sort_mode: SortMode,
+    /// 插入时根据来源列生成 slug
+    pub slug: Option<SlugConfig>,
+    /// 拼音检索列填充规则
+    pub pinyin: Vec<PinyinConfig>,
+    /// 派生列同步规则
+    pub sync: Vec<SyncConfig>,
+    /// 加密函数，签名为 fn(&str) -> Result<String, E>，E: Display
+    pub encrypt_fn: Option<syn::Path>,
+    /// 解密函数，签名为 fn(&str) -> Result<String, E>，E: Display
+    pub decrypt_fn: Option<syn::Path>,
+    /// 硬删除后调用的函数，签名为 async fn(&ActiveModel, &C) -> Result<(), DbErr>，C: ConnectionTrait
+    pub after_delete_fn: Option<syn::Path>,
+    /// 保存、删除后同步检索索引的函数，签名为 async fn(&C, table: &str, id: &str, document: Option<&Model>) -> Result<(), DbErr>，
+    /// document 为 None 表示从索引中移除
+    pub search_sync_fn: Option<syn::Path>,
+    /// 从回收站恢复后调用的函数，签名为 async fn(&C, &Model) -> Result<(), DbErr>，
+    /// 用于重新启用子记录、重建索引、发送通知等
+    pub on_restore: Option<syn::Path>,
+    /// 解析操作人姓名的函数，签名为 fn(user_id: &str) -> Option<String>，
+    /// 上下文只有 user_id 没有 user_name 时用于填充 create_by / update_by
+    pub user_name_fn: Option<syn::Path>,
+    /// anonymize_by_subject 匹配数据主体的列，#[auto_field(pii_subject = "user_id")]，未设置时为 create_id
+    pub pii_subject: Option<String>,
+    /// 批量操作日志表，#[auto_field(ops_log = "operations_log")]，
+    /// 批量软删除、恢复和 update_many_scoped 成功后写入一行汇总
+    pub ops_log: Option<String>,
+    /// 插入前调用的租户配额检查函数，签名为 async fn(&C, table: &str, tenant_id: &str) -> Result<bool, DbErr>，
+    /// 返回 false 表示租户已超出配额
+    pub tenant_quota_fn: Option<syn::Path>,
+    /// 分片键列，#[auto_field(shard_key = "tenant_id", shard_fn = "crate::shard::of")]
+    pub shard_key: Option<String>,
+    /// 分片函数，签名为 fn(&str) -> u32，由分片键的文本值计算分片号
+    pub shard_fn: Option<syn::Path>,
+    /// 实体类型路径，#[auto_field(entity = "UserEntity")]，未设置时为同一模块中的 Entity
+    pub entity: Option<syn::Path>,
+    /// ActiveModel 类型路径，#[auto_field(active_model = "UserActiveModel")]，未设置时为 ActiveModel
+    pub active_model: Option<syn::Path>,
+    /// 列枚举类型路径，#[auto_field(column_enum = "UserColumn")]，未设置时为 Column
+    pub column_enum: Option<syn::Path>,
+    /// auto_field_trait crate 的路径，#[auto_field(trait_path = "::my_traits")]；
+    /// 未设置时按 Cargo.toml 中的依赖重命名推断，找不到时为 ::auto_field_trait
+    pub trait_path: Option<syn::Path>,
+    /// 更新前把旧行复制到的历史表，#[auto_field(archive_to = "user_history")]，
+    /// 历史表包含实体的全部列以及 archived_at、archived_by
+    pub archive_to: Option<String>,
+    /// Diesel 的 table! 模块路径，#[auto_field(diesel_table = "crate::schema::users")]，
+    /// 启用 diesel 特性时生成受管列的变更集 AutoFieldChangeset
+    pub diesel_table: Option<syn::Path>,
+    /// 保存前调用的实体级校验函数，签名为 fn(&ActiveModel, insert: bool) -> Result<(), E>，E: Display
+    pub validate_fn: Option<syn::Path>,
+    /// 行哈希列维护规则
+    pub hash: Option<HashConfig>,
+    /// 不生成任何代码，#[auto_field(skip_all)]
+    pub skip_all: bool,
+    /// 生成代码中运行时消息（日志、panic、错误描述）使用的语言
+    pub messages: MessageLang,
+    /// 展开标记，写入生成代码供下游快照比对
+    pub expansion_id: Option<String>,
+    /// 生成雪花 ID 时捕获生成器 panic，#[auto_field(id_panic_guard)]
+    pub id_panic_guard: bool,
+    /// 生成在指定连接上执行的只读查询（如 find_not_deleted_on），#[auto_field(replica_reads)]
+    pub replica_reads: bool,
+    /// 生成的查询附带 SQL 注释（/* entity=users op=find_not_deleted */），#[auto_field(sql_comment)]
+    pub sql_comment: bool,
+    /// before_save 的主体只按 &dyn ConnectionTrait 生成一份，不随连接类型单态化，#[auto_field(dyn_conn)]
+    pub dyn_conn: bool,
+    /// 生成测试数据工厂 Entity::factory()，#[auto_field(gen_factory)]
+    pub gen_factory: bool,
+    /// update_many / delete_many 绕过约定时的防护方式
+    pub bulk_guard: BulkGuard,
+    /// 雪花 ID 生成器的纪元（Unix 毫秒），用于从 ID 解析生成时间
+    pub snowflake_epoch_ms: Option<i64>,
+    /// 雪花 ID 的租户分片来源，ID 始终由全局组件生成
+    pub id_worker_from: IdWorkerSource,
+    /// 全局填充策略，未设置时插入保留调用方的值、更新覆盖
+    pub fill_policy: Option<FillPolicy>,
+    /// 时间戳的填充策略，优先于 fill_policy
+    pub timestamps_policy: Option<FillPolicy>,
+    /// 审计字段的填充策略，优先于 fill_policy
+    pub audit_policy: Option<FillPolicy>,
+    /// 租户字段的填充策略，优先于 fill_policy
+    pub tenant_policy: Option<FillPolicy>,
+    /// 上下文中没有操作人时保存失败，#[auto_field(audit_required)]
+    pub audit_required: bool,
+    /// 创建人姓名、租户名称是插入时的快照，#[auto_field(denormalize_snapshot)]，
+    /// 插入后任何策略下都不再改动，更新时调用方的赋值被丢弃
+    pub denormalize_snapshot: bool,
+    /// 代操作时记录实际操作人，#[auto_field(impersonation)]，
+    /// 填充 create_proxy_id / update_proxy_id 列
+    pub impersonation: bool,
+    /// 插入时 update_time / update_by / update_id 与创建值保持一致
+    pub init_update_fields_on_insert: bool,
+    /// 软删除 / 恢复时同步 state、state_name
+    pub delete_state: Option<DeleteStateConfig>,
+    /// 受管列（按数据库列名）对应的结构体字段，解析字段后由 bind_fields 填入
+    pub managed_fields: std::collections::BTreeMap<String, syn::Ident>,
+    /// delete_flag 为 Option<bool>，由 bind_fields 根据字段类型设置
+    pub delete_flag_bool: bool,
+    /// create_time / update_time 的存储类型，由 bind_fields 根据字段类型设置
+    pub timestamp_storage: TimestampStorage,
+    /// delete_flag 为 NULL 的记录视为已删除，#[auto_field(null_is_deleted)]；默认视为未删除
+    pub null_is_deleted: bool,
+    /// 软删除时写入 delete_time，恢复时清空，#[auto_field(delete_time)]
+    pub delete_time: bool,
+    /// 按 cfg 条件启用的功能，#[auto_field(tenant = cfg(feature = "multi_tenant"))]，按 (配置项, cfg 谓词) 列出
+    pub conditional: Vec<(String, proc_macro2::TokenStream)>,
+    /// ActiveModel::default() 预置编译期可知的默认值，#[auto_field(active_model_defaults)]
+    pub active_model_defaults: bool,
+    /// 生成只暴露业务列的 ModelBuilder，#[auto_field(gen_builder)]
+    pub gen_builder: bool,
+    /// 生成部分更新载荷 UpdatePayload，#[auto_field(gen_update_payload)]
+    pub gen_update_payload: bool,
+    /// UpdatePayload 额外派生的 trait，#[auto_field(update_payload_derive = ["serde::Deserialize"])]
+    pub update_payload_derive: Vec<syn::Path>,
+    /// 生成按表头导入一行的 ActiveModel::from_row，#[auto_field(gen_import)]
+    pub gen_import: bool,
+    /// 生成导出用的 Model::export_record，#[auto_field(gen_export)]
+    pub gen_export: bool,
+    /// 生成不依赖 ActiveModel 的 Entity::auto_insert_values / auto_update_values，#[auto_field(gen_sea_query)]
+    pub gen_sea_query: bool,
+    /// 生成变更事件载荷 XxxChangedEvent 和 Model::to_change_event，#[auto_field(gen_change_event)]
+    pub gen_change_event: bool,
+    /// 生成 Debezium 格式的变更信封 Model::to_cdc_envelope，#[auto_field(gen_cdc_envelope)]
+    pub gen_cdc_envelope: bool,
+    /// SQLite 兼容模式，不依赖 SQLite 缺少的数据库特性，#[auto_field(sqlite_compat = true)]
+    pub sqlite_compat: bool,
+}
+
+/// 运行时消息语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum MessageLang {
+    #[default]
+    En,
+    Zh,
+}
+
+impl MessageLang {
+    /// 从字符串字面量解析语言
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "en" => Ok(Self::En),
+            "zh" => Ok(Self::Zh),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown messages language: {}, expected \"en\" or \"zh\"", other)
+            )),
+        }
+    }
+}
+
+/// 生成代码中的运行时消息，均为 format 字符串
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Message {
+    /// 未找到雪花 ID 生成器组件
+    GeneratorMissing,
+    /// 批量插入前的调试日志，参数为记录数
+    BatchInsertPrepared,
+    /// 加密失败，参数为列名和错误
+    EncryptFailed,
+    /// 记录不存在，参数为 ID
+    RecordNotFound,
+    /// 校验失败：值为空
+    MustNotBeEmpty,
+    /// 校验失败：长度不足，参数为最小长度
+    TooShort,
+    /// 校验失败：长度超出，参数为最大长度
+    TooLong,
+}
+
+impl Message {
+    /// 按语言返回消息文本
+    fn text(self, lang: MessageLang) -> &'static str {
+        match (self, lang) {
+            (Self::GeneratorMissing, MessageLang::En) => {
+                "No SnowflakeIdGenerator component found in the Spring application context."
+            }
+            (Self::GeneratorMissing, MessageLang::Zh) => "Spring 应用上下文中未找到雪花ID生成器组件（SnowflakeIdGenerator）",
+            (Self::BatchInsertPrepared, MessageLang::En) => "Prepared {} ActiveModels for batch insert",
+            (Self::BatchInsertPrepared, MessageLang::Zh) => "准备执行批量插入，处理后共 {} 个ActiveModel",
+            (Self::EncryptFailed, MessageLang::En) => "Failed to encrypt {}: {}",
+            (Self::EncryptFailed, MessageLang::Zh) => "字段 {} 加密失败：{}",
+            (Self::RecordNotFound, MessageLang::En) => "{} not found",
+            (Self::RecordNotFound, MessageLang::Zh) => "记录 {} 不存在",
+            (Self::MustNotBeEmpty, MessageLang::En) => "must not be empty",
+            (Self::MustNotBeEmpty, MessageLang::Zh) => "不能为空",
+            (Self::TooShort, MessageLang::En) => "must be at least {} characters",
+            (Self::TooShort, MessageLang::Zh) => "长度不能少于 {} 个字符",
+            (Self::TooLong, MessageLang::En) => "must be at most {} characters",
+            (Self::TooLong, MessageLang::Zh) => "长度不能超过 {} 个字符",
+        }
+    }
+}
+
+/// 行哈希配置，#[auto_field(hash(from = ["col_a", "col_b"], into = "row_hash"))]
+#[derive(Debug, Clone)]
+struct HashConfig {
+    /// 参与哈希的业务列
+    pub from: Vec<String>,
+    /// 哈希结果列
+    pub into: String,
+}
+
+impl HashConfig {
+    /// 从 hash(...) 列表中解析配置
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut from = Vec::new();
+        let mut into = None;
+        for name_value in parse_nested_name_values(list)? {
+            let key = name_value.path.get_ident()
+                .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                .to_string();
+            match key.as_str() {
+                "from" => from = parse_str_list_value(&name_value.value)?,
+                "into" => into = Some(parse_str_value(&name_value.value)?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        format!("Unknown hash configuration key: {}", key)
+                    ));
+                }
+            }
+        }
+
+        if from.is_empty() {
+            return Err(syn::Error::new_spanned(list, "hash requires from = [\"<column>\", ...]"));
+        }
+        let into = into.ok_or_else(|| syn::Error::new_spanned(list, "hash requires into = \"<column>\""))?;
+        Ok(Self { from, into })
+    }
+}
+
+/// 拼音检索列配置，#[auto_field(pinyin(from = "name", full = "name_pinyin", initials = "name_py"))]
+#[derive(Debug, Clone)]
+struct PinyinConfig {
+    /// 来源列
+    pub from: String,
+    /// 全拼列
+    pub full: Option<String>,
+    /// 首字母列
+    pub initials: Option<String>,
+}
+
+impl PinyinConfig {
+    /// 从 pinyin(...) 列表中解析配置
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        if !cfg!(feature = "pinyin") {
+            return Err(syn::Error::new_spanned(
+                list,
+                "pinyin requires the `pinyin` feature of auto_field_macros"
+            ));
+        }
+
+        let mut from = None;
+        let mut full = None;
+        let mut initials = None;
+        for name_value in parse_nested_name_values(list)? {
+            let key = name_value.path.get_ident()
+                .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                .to_string();
+            match key.as_str() {
+                "from" => from = Some(parse_str_value(&name_value.value)?),
+                "full" => full = Some(parse_str_value(&name_value.value)?),
+                "initials" => initials = Some(parse_str_value(&name_value.value)?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        format!("Unknown pinyin configuration key: {}", key)
+                    ));
+                }
+            }
+        }
+
+        let from = from.ok_or_else(|| syn::Error::new_spanned(list, "pinyin requires from = \"<column>\""))?;
+        if full.is_none() && initials.is_none() {
+            return Err(syn::Error::new_spanned(list, "pinyin requires full and/or initials target columns"));
+        }
+        Ok(Self { from, full, initials })
+    }
+}
+
+/// slug 生成配置，#[auto_field(slug(from = "title", into = "slug"))]
+#[derive(Debug, Clone)]
+struct SlugConfig {
+    /// 来源列
+    pub from: String,
+    /// 目标列，默认为 slug
+    pub into: String,
+    /// 自定义音译函数，签名为 fn(&str) -> String
+    pub transliterate: Option<syn::Path>,
+}
+
+impl SlugConfig {
+    /// 从 slug(...) 列表中解析配置
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut from = None;
+        let mut into = "slug".to_string();
+        let mut transliterate = None;
+
+        for name_value in parse_nested_name_values(list)? {
+            let key = name_value.path.get_ident()
+                .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                .to_string();
+            match key.as_str() {
+                "from" => from = Some(parse_str_value(&name_value.value)?),
+                "into" => into = parse_str_value(&name_value.value)?,
+                "transliterate" => transliterate = Some(parse_path_value(&name_value.value)?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        format!("Unknown slug configuration key: {}", key)
+                    ));
+                }
+            }
+        }
+
+        let from = from.ok_or_else(|| syn::Error::new_spanned(list, "slug requires from = \"<column>\""))?;
+        Ok(Self { from, into, transliterate })
+    }
+}
+
+/// 派生列同步规则，#[auto_field(sync(from = "currency", into = "currency_symbol", map = "my_app::maps::symbol"))]
+#[derive(Debug, Clone)]
+struct SyncConfig {
+    /// 来源列
+    pub from: String,
+    /// 目标列
+    pub into: String,
+    /// 映射函数，签名为 fn(&来源列类型) -> 目标列类型
+    pub map: syn::Path,
+}
+
+impl SyncConfig {
+    /// 从 sync(...) 列表中解析配置
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut from = None;
+        let mut into = None;
+        let mut map = None;
+        for name_value in parse_nested_name_values(list)? {
+            let key = name_value.path.get_ident()
+                .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                .to_string();
+            match key.as_str() {
+                "from" => from = Some(parse_str_value(&name_value.value)?),
+                "into" => into = Some(parse_str_value(&name_value.value)?),
+                "map" => map = Some(parse_path_value(&name_value.value)?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        format!("Unknown sync configuration key: {}", key)
+                    ));
+                }
+            }
+        }
+
+        let from = from.ok_or_else(|| syn::Error::new_spanned(list, "sync requires from = \"<column>\""))?;
+        let into = into.ok_or_else(|| syn::Error::new_spanned(list, "sync requires into = \"<column>\""))?;
+        let map = map.ok_or_else(|| syn::Error::new_spanned(list, "sync requires map = \"<function path>\""))?;
+        Ok(Self { from, into, map })
+    }
+}
+
+/// 软删除时同步的状态配置，#[auto_field(delete_sets_state = "0")] 或
+/// #[auto_field(delete_sets_state(state = "0", state_name = "禁用", restore_state = "1", restore_state_name = "启用"))]
+#[derive(Debug, Clone)]
+struct DeleteStateConfig {
+    /// 删除时写入 state 的值
+    pub state: String,
+    /// 删除时写入 state_name 的值
+    pub state_name: Option<String>,
+    /// 恢复时写回 state 的值，未设置时恢复不改动 state
+    pub restore_state: Option<String>,
+    /// 恢复时写回 state_name 的值
+    pub restore_state_name: Option<String>,
+}
+
+impl DeleteStateConfig {
+    /// 从 delete_sets_state = "..." 解析配置
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        Ok(Self {
+            state: parse_str_value(expr)?,
+            state_name: None,
+            restore_state: None,
+            restore_state_name: None,
+        })
+    }
+
+    /// 从 delete_sets_state(...) 列表中解析配置
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut state = None;
+        let mut state_name = None;
+        let mut restore_state = None;
+        let mut restore_state_name = None;
+
+        for name_value in parse_nested_name_values(list)? {
+            let key = name_value.path.get_ident()
+                .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                .to_string();
+            match key.as_str() {
+                "state" => state = Some(parse_str_value(&name_value.value)?),
+                "state_name" => state_name = Some(parse_str_value(&name_value.value)?),
+                "restore_state" => restore_state = Some(parse_str_value(&name_value.value)?),
+                "restore_state_name" => restore_state_name = Some(parse_str_value(&name_value.value)?),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        format!("Unknown delete_sets_state configuration key: {}", key)
+                    ));
+                }
+            }
+        }
+
+        let state = state.ok_or_else(|| syn::Error::new_spanned(list, "delete_sets_state requires state = \"<value>\""))?;
+        Ok(Self { state, state_name, restore_state, restore_state_name })
+    }
+}
+
+/// 排序列的插入填充方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum SortMode {
+    /// 填充 0
+    #[default]
+    Zero,
+    /// 填充当前最大值 + 1，追加到末尾
+    Append,
+}
+
+impl SortMode {
+    /// 从字符串字面量解析填充方式
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "zero" => Ok(Self::Zero),
+            "append" => Ok(Self::Append),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown sort_mode: {}, expected \"zero\" or \"append\"", other)
+            )),
+        }
+    }
+}
+
+/// update_many / delete_many 的防绕过方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum BulkGuard {
+    /// 不生成
+    #[default]
+    Off,
+    /// 生成标记为 deprecated 的同名固有方法，调用处产生警告
+    Deprecate,
+    /// 生成同名固有方法，自动注入受管的 SET / WHERE 子句
+    Wrap,
+}
+
+impl BulkGuard {
+    /// 从字符串字面量解析防绕过方式
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "off" => Ok(Self::Off),
+            "deprecate" => Ok(Self::Deprecate),
+            "wrap" => Ok(Self::Wrap),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown bulk_guard: {}, expected \"off\", \"deprecate\" or \"wrap\"", other)
+            )),
+        }
+    }
+}
+
+/// 受管列的填充策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FillPolicy {
+    /// 调用方已赋值时保留调用方的值
+    Protect,
+    /// 总是使用生成代码的取值覆盖调用方的值
+    Overwrite,
+}
+
+impl FillPolicy {
+    /// 从字符串字面量解析填充策略
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "protect" => Ok(Self::Protect),
+            "overwrite" => Ok(Self::Overwrite),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown fill policy: {}, expected \"protect\" or \"overwrite\"", other)
+            )),
+        }
+    }
+}
+
+/// 解析功能开关的值：布尔值，或表示启用并指定填充策略的字符串，如 timestamps = "overwrite"
+fn parse_feature_value(expr: &Expr) -> syn::Result<(bool, Option<FillPolicy>)> {
+    match expr {
+        Expr::Lit(syn::ExprLit { lit: Lit::Str(_), .. }) => Ok((true, Some(FillPolicy::from_expr(expr)?))),
+        _ => Ok((parse_bool_value(expr)?, None)),
+    }
+}
+
+/// 时间戳的取值来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TimestampSource {
+    /// 由应用在保存时填充 Utc::now()
+    #[default]
+    Application,
+    /// 由数据库列默认值（DEFAULT / ON UPDATE CURRENT_TIMESTAMP）填充
+    Database,
+}
+
+/// create_time / update_time 列的存储类型，填充时由 UTC 当前时间转换，读取时统一转换为 DateTime<Utc>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TimestampStorage {
+    /// Option<NaiveDateTime>（sea_orm 的 DateTime），按 UTC 存储
+    #[default]
+    Naive,
+    /// Option<DateTime<Utc>>（DateTimeUtc）
+    Utc,
+    /// Option<DateTime<FixedOffset>>（DateTimeWithTimeZone）
+    WithTimeZone,
+    /// Option<i64>，Unix 毫秒
+    Millis,
+}
+
+impl TimestampStorage {
+    /// 由 Option 内层类型判断存储类型，不是支持的时间类型时返回 None
+    fn of(ty: &syn::Type) -> Option<Self> {
+        if type_last_segment(ty).is_some_and(|segment| segment.ident == "i64") {
+            return Some(Self::Millis);
+        }
+        let segment = type_last_segment(ty)?;
+        match &segment.arguments {
+            syn::PathArguments::None if segment.ident == "DateTime" || segment.ident == "NaiveDateTime" => Some(Self::Naive),
+            syn::PathArguments::None if segment.ident == "DateTimeUtc" => Some(Self::Utc),
+            syn::PathArguments::None if segment.ident == "DateTimeWithTimeZone" => Some(Self::WithTimeZone),
+            syn::PathArguments::AngleBracketed(arguments) if segment.ident == "DateTime" => {
+                let Some(syn::GenericArgument::Type(zone)) = arguments.args.first() else {
+                    return None;
+                };
+                match type_last_segment(zone)?.ident.to_string().as_str() {
+                    "Utc" => Some(Self::Utc),
+                    "FixedOffset" => Some(Self::WithTimeZone),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TimestampSource {
+    /// 从字符串字面量解析时间戳来源
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "application" => Ok(Self::Application),
+            "database" => Ok(Self::Database),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown timestamps_source: {}, expected \"application\" or \"database\"", other)
+            )),
+        }
+    }
+}
+
+/// 雪花 ID 的分片来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum IdWorkerSource {
+    /// 使用全局组件注册表中的生成器
+    #[default]
+    Component,
+    /// 按当前上下文的租户 ID 推导分片号，追加在 ID 末尾，每个租户固定落在同一个分片上
+    Tenant,
+}
+
+impl IdWorkerSource {
+    /// 从字符串字面量解析 worker 位来源
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "component" => Ok(Self::Component),
+            "tenant" => Ok(Self::Tenant),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown id_worker_from: {}, expected \"component\" or \"tenant\"", other)
+            )),
+        }
+    }
+}
+
+/// 时间戳截断精度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimestampPrecision {
+    Seconds,
+    Millis,
+    Micros,
+}
+
+impl TimestampPrecision {
+    /// 从字符串字面量解析精度
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "seconds" => Ok(Self::Seconds),
+            "millis" => Ok(Self::Millis),
+            "micros" => Ok(Self::Micros),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown timestamp_precision: {}, expected \"seconds\", \"millis\" or \"micros\"", other)
+            )),
+        }
+    }
+
+    /// 保留的小数秒位数
+    fn subsec_digits(self) -> u16 {
+        match self {
+            Self::Seconds => 0,
+            Self::Millis => 3,
+            Self::Micros => 6,
+        }
+    }
+}
+
+/// DDL 输出使用的数据库方言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlDialect {
+    MySql,
+    Postgres,
+}
+
+impl SqlDialect {
+    /// 从字符串字面量解析方言
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "mysql" => Ok(Self::MySql),
+            "postgres" => Ok(Self::Postgres),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!("Unknown SQL dialect: {}, expected \"mysql\" or \"postgres\"", other)
+            )),
+        }
+    }
 }
 
 impl AutoFieldConfig {
+    /// 记录受管列对应的结构体字段，字段可以通过 #[sea_orm(column_name = "...")] 映射到受管列
+    pub fn bind_fields(&mut self, fields: &EntityFields) {
+        let columns: Vec<String> = self.managed_columns().into_iter().map(String::from).collect();
+        for column in columns {
+            if let Some(field) = fields.get(&column) {
+                self.managed_fields.insert(column, field.ident.clone());
+            }
+        }
+        self.delete_flag_bool = self.soft_delete
+            && fields.get("delete_flag").is_some_and(|field| option_inner_type(&field.ty).is_some_and(is_bool_type));
+        if self.timestamps {
+            self.timestamp_storage = fields
+                .get("create_time")
+                .and_then(|field| option_inner_type(&field.ty))
+                .and_then(TimestampStorage::of)
+                .unwrap_or_default();
+        }
+    }
+
+    /// 把 UTC 的 NaiveDateTime 表达式转换为 create_time / update_time 的存储类型
+    pub fn timestamp_value(&self, now: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self.timestamp_storage {
+            TimestampStorage::Naive => now,
+            TimestampStorage::Utc => quote! { (#now).and_utc() },
+            TimestampStorage::WithTimeZone => quote! { (#now).and_utc().fixed_offset() },
+            TimestampStorage::Millis => quote! { (#now).and_utc().timestamp_millis() },
+        }
+    }
+
+    /// 把 create_time / update_time 的存储值转换为 Option<DateTime<Utc>>
+    pub fn timestamp_to_utc(&self, time: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self.timestamp_storage {
+            TimestampStorage::Naive => quote! { Some((#time).and_utc()) },
+            TimestampStorage::Utc => quote! { Some(#time) },
+            TimestampStorage::WithTimeZone => quote! { Some((#time).with_timezone(&chrono::Utc)) },
+            TimestampStorage::Millis => quote! { chrono::DateTime::from_timestamp_millis(#time) },
+        }
+    }
+
+    /// 未删除记录的 delete_flag 取值，整数列为 0，布尔列为 false
+    pub fn not_deleted_value(&self) -> proc_macro2::TokenStream {
+        if self.delete_flag_bool {
+            quote! { false }
+        } else {
+            quote! { 0 }
+        }
+    }
+
+    /// 已删除记录的 delete_flag 取值，整数列为 1，布尔列为 true
+    pub fn deleted_value(&self) -> proc_macro2::TokenStream {
+        if self.delete_flag_bool {
+            quote! { true }
+        } else {
+            quote! { 1 }
+        }
+    }
+
+    /// 未删除记录的过滤条件。默认 delete_flag 为 NULL 的旧数据视为未删除，
+    /// 开启 null_is_deleted 时只有等于未删除值的记录才算未删除
+    pub fn not_deleted_condition(&self) -> proc_macro2::TokenStream {
+        let column = self.column("delete_flag");
+        let column_type = self.column_type();
+        if self.null_is_deleted {
+            let not_deleted = self.not_deleted_value();
+            quote! { sea_orm::ColumnTrait::eq(&#column_type::#column, #not_deleted) }
+        } else {
+            let deleted = self.deleted_value();
+            quote! {
+                sea_orm::Condition::any()
+                    .add(sea_orm::ColumnTrait::is_null(&#column_type::#column))
+                    .add(sea_orm::ColumnTrait::ne(&#column_type::#column, #deleted))
+            }
+        }
+    }
+
+    /// 已删除记录（回收站）的过滤条件，与 not_deleted_condition 互补
+    pub fn deleted_condition(&self) -> proc_macro2::TokenStream {
+        let column = self.column("delete_flag");
+        let column_type = self.column_type();
+        if self.null_is_deleted {
+            let not_deleted = self.not_deleted_value();
+            quote! {
+                sea_orm::Condition::any()
+                    .add(sea_orm::ColumnTrait::is_null(&#column_type::#column))
+                    .add(sea_orm::ColumnTrait::ne(&#column_type::#column, #not_deleted))
+            }
+        } else {
+            let deleted = self.deleted_value();
+            quote! { sea_orm::ColumnTrait::eq(&#column_type::#column, #deleted) }
+        }
+    }
+
+    /// 判断已查询出的记录是否已删除的表达式，flag 为 Option 类型的 delete_flag 值
+    pub fn is_deleted_check(&self, flag: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.null_is_deleted {
+            let not_deleted = self.not_deleted_value();
+            quote! { #flag != Some(#not_deleted) }
+        } else {
+            let deleted = self.deleted_value();
+            quote! { #flag == Some(#deleted) }
+        }
+    }
+
+    /// 未删除条件的文档描述
+    pub fn not_deleted_description(&self) -> String {
+        if self.null_is_deleted {
+            format!("delete_flag = {}", self.not_deleted_value())
+        } else {
+            format!("delete_flag IS NULL OR delete_flag <> {}", self.deleted_value())
+        }
+    }
+
+    /// 受管列对应的结构体字段名，如 field("create_time") 对 `created` 字段返回 created
+    pub fn field(&self, column: &str) -> syn::Ident {
+        self.managed_fields
+            .get(column)
+            .cloned()
+            .unwrap_or_else(|| syn::Ident::new(column, proc_macro2::Span::call_site()))
+    }
+
+    /// 受管列对应的 Column 变体，SeaORM 按字段名而不是 column_name 生成变体
+    pub fn column(&self, column: &str) -> syn::Ident {
+        let field = self.field(column);
+        column_variant(&field.unraw().to_string(), field.span())
+    }
+
     /// 从属性中解析配置
     pub fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
         let mut config = Self::default();
-
         for attr in attrs {
             if attr.path().is_ident("auto_field") {
                 match &attr.meta {
@@ -35,18 +816,34 @@ impl AutoFieldConfig {
                                         .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
                                         .to_string();
 
+                                    // 后出现的配置覆盖先前的 cfg 条件
+                                    config.conditional.retain(|(conditional, _)| *conditional != key);
+                                    if let Some(predicate) = parse_cfg_value(&name_value.value) {
+                                        if !CONDITIONAL_KEYS.contains(&key.as_str()) {
+                                            return Err(syn::Error::new_spanned(
+                                                &name_value.value,
+                                                format!("auto_field configuration key {} does not support cfg(...), expected one of: {}", key, CONDITIONAL_KEYS.join(", "))
+                                            ));
+                                        }
+                                        config.conditional.push((key, predicate));
+                                        continue;
+                                    }
+
                                     match key.as_str() {
                                         "snowflake_id" => {
                                             config.snowflake_id = parse_bool_value(&name_value.value)?;
                                         }
                                         "timestamps" => {
-                                            config.timestamps = parse_bool_value(&name_value.value)?;
+                                            (config.timestamps, config.timestamps_policy) = parse_feature_value(&name_value.value)?;
                                         }
                                         "audit" => {
-                                            config.audit = parse_bool_value(&name_value.value)?;
+                                            (config.audit, config.audit_policy) = parse_feature_value(&name_value.value)?;
                                         }
                                         "tenant" => {
-                                            config.tenant = parse_bool_value(&name_value.value)?;
+                                            (config.tenant, config.tenant_policy) = parse_feature_value(&name_value.value)?;
+                                        }
+                                        "fill_policy" => {
+                                            config.fill_policy = Some(FillPolicy::from_expr(&name_value.value)?);
                                         }
                                         "version" => {
                                             config.version = parse_bool_value(&name_value.value)?;
@@ -54,6 +851,182 @@ impl AutoFieldConfig {
                                         "soft_delete" => {
                                             config.soft_delete = parse_bool_value(&name_value.value)?;
                                         }
+                                        "emit_ddl" => {
+                                            config.emit_ddl = Some(SqlDialect::from_expr(&name_value.value)?);
+                                        }
+                                        "emit_index_hints" => {
+                                            config.emit_index_hints = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "skip_all" => {
+                                            config.skip_all = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "id_panic_guard" => {
+                                            config.id_panic_guard = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "replica_reads" => {
+                                            config.replica_reads = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "sql_comment" => {
+                                            config.sql_comment = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "dyn_conn" => {
+                                            config.dyn_conn = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_factory" => {
+                                            config.gen_factory = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "impersonation" => {
+                                            config.impersonation = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "denormalize_snapshot" => {
+                                            config.denormalize_snapshot = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "audit_required" => {
+                                            config.audit_required = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "init_update_fields_on_insert" => {
+                                            config.init_update_fields_on_insert = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "active_model_defaults" => {
+                                            config.active_model_defaults = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "delete_time" => {
+                                            config.delete_time = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "null_is_deleted" => {
+                                            config.null_is_deleted = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_builder" => {
+                                            config.gen_builder = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_update_payload" => {
+                                            config.gen_update_payload = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_import" => {
+                                            config.gen_import = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_export" => {
+                                            config.gen_export = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_sea_query" => {
+                                            config.gen_sea_query = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_change_event" => {
+                                            config.gen_change_event = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "gen_cdc_envelope" => {
+                                            config.gen_cdc_envelope = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "sqlite_compat" => {
+                                            config.sqlite_compat = parse_bool_value(&name_value.value)?;
+                                        }
+                                        "update_payload_derive" => {
+                                            config.update_payload_derive = match &name_value.value {
+                                                Expr::Array(array) => array.elems.iter().map(parse_path_value).collect::<syn::Result<_>>()?,
+                                                value => vec![parse_path_value(value)?],
+                                            };
+                                        }
+                                        "delete_sets_state" => {
+                                            config.delete_state = Some(DeleteStateConfig::from_expr(&name_value.value)?);
+                                        }
+                                        "messages" => {
+                                            config.messages = MessageLang::from_expr(&name_value.value)?;
+                                        }
+                                        "expansion_id" => {
+                                            config.expansion_id = Some(parse_str_value(&name_value.value)?);
+                                        }
+                                        "timestamps_source" => {
+                                            config.timestamps_source = TimestampSource::from_expr(&name_value.value)?;
+                                        }
+                                        "clock_fn" => {
+                                            config.clock_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "timestamp_precision" => {
+                                            config.timestamp_precision = Some(TimestampPrecision::from_expr(&name_value.value)?);
+                                        }
+                                        "ttl" => {
+                                            config.ttl_seconds = Some(parse_duration_value(&name_value.value)?);
+                                        }
+                                        "retention" => {
+                                            config.retention_seconds = Some(parse_duration_value(&name_value.value)?);
+                                        }
+                                        "sort" => {
+                                            // sort = true 使用默认列名，sort = "display_order" 指定列名
+                                            config.sort_column = match &name_value.value {
+                                                Expr::Lit(syn::ExprLit { lit: Lit::Str(lit_str), .. }) => Some(lit_str.value()),
+                                                value => parse_bool_value(value)?.then(|| "sort".to_string()),
+                                            };
+                                        }
+                                        "sort_mode" => {
+                                            config.sort_mode = SortMode::from_expr(&name_value.value)?;
+                                        }
+                                        "bulk_guard" => {
+                                            config.bulk_guard = BulkGuard::from_expr(&name_value.value)?;
+                                        }
+                                        "snowflake_epoch_ms" => {
+                                            config.snowflake_epoch_ms = Some(parse_int_value(&name_value.value)?);
+                                        }
+                                        "id_worker_from" => {
+                                            config.id_worker_from = IdWorkerSource::from_expr(&name_value.value)?;
+                                        }
+                                        "encrypt_fn" => {
+                                            config.encrypt_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "decrypt_fn" => {
+                                            config.decrypt_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "pii_subject" => {
+                                            config.pii_subject = Some(parse_str_value(&name_value.value)?);
+                                        }
+                                        "user_name_fn" => {
+                                            config.user_name_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "on_restore" => {
+                                            config.on_restore = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "search_sync_fn" => {
+                                            config.search_sync_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "after_delete_fn" => {
+                                            config.after_delete_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "tenant_quota_fn" => {
+                                            config.tenant_quota_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "shard_key" => {
+                                            config.shard_key = Some(parse_str_value(&name_value.value)?);
+                                        }
+                                        "shard_fn" => {
+                                            config.shard_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "validate_fn" => {
+                                            config.validate_fn = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "trait_path" => {
+                                            config.trait_path = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        // 预设由 AutoField 展开前通过对应的 macro_rules! 注入
+                                        "extends" => {
+                                            parse_str_value(&name_value.value)?;
+                                        }
+                                        "ops_log" => {
+                                            config.ops_log = Some(parse_str_value(&name_value.value)?);
+                                        }
+                                        "archive_to" => {
+                                            config.archive_to = Some(parse_str_value(&name_value.value)?);
+                                        }
+                                        "diesel_table" => {
+                                            config.diesel_table = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "entity" => {
+                                            config.entity = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "active_model" => {
+                                            config.active_model = Some(parse_path_value(&name_value.value)?);
+                                        }
+                                        "column_enum" => {
+                                            config.column_enum = Some(parse_path_value(&name_value.value)?);
+                                        }
                                         _ => {
                                             return Err(syn::Error::new_spanned(
                                                 &name_value.path,
@@ -62,11 +1035,32 @@ impl AutoFieldConfig {
                                         }
                                     }
                                 }
+                                Meta::List(list) => {
+                                    // 处理 #[auto_field(slug(from = "title"))] 格式
+                                    let key = list.path.get_ident()
+                                        .ok_or_else(|| syn::Error::new_spanned(&list.path, "Expected identifier"))?
+                                        .to_string();
+
+                                    match key.as_str() {
+                                        "slug" => config.slug = Some(SlugConfig::from_meta_list(&list)?),
+                                        "delete_sets_state" => config.delete_state = Some(DeleteStateConfig::from_meta_list(&list)?),
+                                        "pinyin" => config.pinyin.push(PinyinConfig::from_meta_list(&list)?),
+                                        "hash" => config.hash = Some(HashConfig::from_meta_list(&list)?),
+                                        "sync" => config.sync.push(SyncConfig::from_meta_list(&list)?),
+                                        _ => {
+                                            return Err(syn::Error::new_spanned(
+                                                &list.path,
+                                                format!("Unknown auto_field configuration key: {}", key)
+                                            ));
+                                        }
+                                    }
+                                }
                                 Meta::Path(path) => {
                                     // 处理 #[auto_field(snowflake_id)] 格式 (默认为 true)
                                     let key = path.get_ident()
                                         .ok_or_else(|| syn::Error::new_spanned(&path, "Expected identifier"))?
                                         .to_string();
+                                    config.conditional.retain(|(conditional, _)| *conditional != key);
 
                                     match key.as_str() {
                                         "snowflake_id" => config.snowflake_id = true,
@@ -75,6 +1069,37 @@ impl AutoFieldConfig {
                                         "tenant" => config.tenant = true,
                                         "version" => config.version = true,
                                         "soft_delete" => config.soft_delete = true,
+                                        "emit_index_hints" => config.emit_index_hints = true,
+                                        "skip_all" => config.skip_all = true,
+                                        "id_panic_guard" => config.id_panic_guard = true,
+                                        "replica_reads" => config.replica_reads = true,
+                                        "sql_comment" => config.sql_comment = true,
+                                        "dyn_conn" => config.dyn_conn = true,
+                                        "gen_factory" => config.gen_factory = true,
+                                        "audit_required" => config.audit_required = true,
+                                        "impersonation" => config.impersonation = true,
+                                        "denormalize_snapshot" => config.denormalize_snapshot = true,
+                                        "init_update_fields_on_insert" => config.init_update_fields_on_insert = true,
+                                        "active_model_defaults" => config.active_model_defaults = true,
+                                        "null_is_deleted" => config.null_is_deleted = true,
+                                        "delete_time" => config.delete_time = true,
+                                        "gen_builder" => config.gen_builder = true,
+                                        "gen_update_payload" => config.gen_update_payload = true,
+                                        "gen_import" => config.gen_import = true,
+                                        "gen_export" => config.gen_export = true,
+                                        "gen_sea_query" => config.gen_sea_query = true,
+                                        "gen_change_event" => config.gen_change_event = true,
+                                        "gen_cdc_envelope" => config.gen_cdc_envelope = true,
+                                        "sqlite_compat" => config.sqlite_compat = true,
+                                        "sort" => config.sort_column = Some("sort".to_string()),
+                                        "emit_ddl" | "timestamps_source" | "clock_fn" | "timestamp_precision" | "ttl" | "retention" | "sort_mode"
+                                        | "encrypt_fn" | "decrypt_fn" | "after_delete_fn" | "search_sync_fn" | "on_restore" | "user_name_fn" | "pii_subject" | "tenant_quota_fn" | "shard_key" | "shard_fn" | "validate_fn" | "update_payload_derive" | "trait_path" | "extends" | "archive_to" | "diesel_table" | "ops_log" | "entity" | "active_model" | "column_enum" | "messages" | "expansion_id" | "bulk_guard"
+                                        | "snowflake_epoch_ms" | "id_worker_from" | "fill_policy" | "delete_sets_state" => {
+                                            return Err(syn::Error::new_spanned(
+                                                &path,
+                                                format!("auto_field configuration key {} requires a value", key)
+                                            ));
+                                        }
                                         _ => {
                                             return Err(syn::Error::new_spanned(
                                                 &path,
@@ -83,25 +1108,12 @@ impl AutoFieldConfig {
                                         }
                                     }
                                 }
-                                _ => {
-                                    return Err(syn::Error::new_spanned(
-                                        &meta,
-                                        "Expected key = value or key format in auto_field attribute"
-                                    ));
-                                }
                             }
                         }
                     }
                     Meta::Path(_) => {
                         // #[auto_field] 没有参数，使用默认配置
-                        config = Self {
-                            snowflake_id: false,
-                            timestamps: false,
-                            audit: false,
-                            tenant: false,
-                            version: false,
-                            soft_delete: false,
-                        };
+                        config = Self::default();
                     }
                     _ => {
                         return Err(syn::Error::new_spanned(
@@ -126,348 +1138,6574 @@ impl AutoFieldConfig {
             ));
         }
 
-        Ok(())
-    }
-}
+        if self.timestamps_source == TimestampSource::Database && !self.timestamps {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "timestamps_source requires timestamps to be enabled"
+            ));
+        }
 
-/// 解析布尔值
-fn parse_bool_value(expr: &Expr) -> syn::Result<bool> {
-    match expr {
+        if self.sort_mode == SortMode::Append && self.sort_column.is_none() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "sort_mode requires sort to be enabled"
+            ));
+        }
+
+        if self.audit_required && !self.audit {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "audit_required requires audit to be enabled"
+            ));
+        }
+
+        if self.init_update_fields_on_insert && !self.timestamps && !self.audit {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "init_update_fields_on_insert requires timestamps or audit to be enabled"
+            ));
+        }
+
+        if self.null_is_deleted && !self.soft_delete {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "null_is_deleted requires soft_delete to be enabled"
+            ));
+        }
+
+        if self.delete_time && !self.soft_delete {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "delete_time requires soft_delete to be enabled"
+            ));
+        }
+
+        if self.delete_state.is_some() && !self.soft_delete {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "delete_sets_state requires soft_delete to be enabled"
+            ));
+        }
+
+        if self.snowflake_epoch_ms.is_some() && !self.snowflake_id {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "snowflake_epoch_ms requires snowflake_id to be enabled"
+            ));
+        }
+
+        if !self.update_payload_derive.is_empty() && !self.gen_update_payload {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "update_payload_derive requires gen_update_payload to be enabled"
+            ));
+        }
+
+        // 删除时间不能从 update_time 推断：已删除的记录再被更新会推迟清理，恢复前的旧删除时间也会提前清理
+        if self.retention_seconds.is_some() && !(self.soft_delete && self.delete_time) {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "retention requires soft_delete and delete_time to be enabled, the deletion time is read from delete_time"
+            ));
+        }
+
+        if self.on_restore.is_some() && !self.soft_delete {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "on_restore requires soft_delete to be enabled"
+            ));
+        }
+
+        if self.diesel_table.is_some() && !cfg!(feature = "diesel") {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "diesel_table requires the `diesel` feature of auto_field_macros"
+            ));
+        }
+
+        if self.denormalize_snapshot && !(self.audit || self.tenant) {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "denormalize_snapshot requires audit or tenant to be enabled"
+            ));
+        }
+
+        if self.impersonation && !self.audit {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "impersonation requires audit to be enabled"
+            ));
+        }
+
+        if self.user_name_fn.is_some() && !self.audit {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "user_name_fn requires audit to be enabled"
+            ));
+        }
+
+        if self.tenant_quota_fn.is_some() && !self.tenant {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "tenant_quota_fn requires tenant to be enabled"
+            ));
+        }
+
+        if self.shard_key.is_some() != self.shard_fn.is_some() {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "shard_key and shard_fn must be configured together"
+            ));
+        }
+
+        if self.id_worker_from != IdWorkerSource::Component && !self.snowflake_id {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "id_worker_from requires snowflake_id to be enabled"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 是否由生成代码在应用侧填充时间戳。SQLite 没有 ON UPDATE CURRENT_TIMESTAMP，
+    /// sqlite_compat 下由数据库维护的时间戳同样改为应用侧填充
+    pub fn fills_timestamps(&self) -> bool {
+        self.timestamps && (self.timestamps_source == TimestampSource::Application || self.sqlite_compat)
+    }
+
+    /// 生成的自检使用的数据库后端，sqlite_compat 下按 SQLite 生成语句
+    pub fn selftest_backend(&self) -> proc_macro2::TokenStream {
+        if self.sqlite_compat {
+            quote! { sea_orm::DbBackend::Sqlite }
+        } else {
+            quote! { sea_orm::DbBackend::Postgres }
+        }
+    }
+
+    /// 功能的填充策略，未单独设置时取全局策略
+    pub fn policy(&self, feature_policy: Option<FillPolicy>) -> Option<FillPolicy> {
+        feature_policy.or(self.fill_policy)
+    }
+
+    /// 按配置的语言返回运行时消息
+    pub fn message(&self, message: Message) -> &'static str {
+        message.text(self.messages)
+    }
+
+    /// 生成的填充逻辑是否需要当前时间
+    pub fn needs_now(&self) -> bool {
+        self.fills_timestamps() || self.ttl_seconds.is_some()
+    }
+
+    /// 生成的填充逻辑是否需要读取 AutoFieldContext，只有审计和租户字段依赖上下文
+    pub fn needs_context(&self) -> bool {
+        self.audit || self.tenant
+    }
+
+    /// 生成获取当前上下文的语句，不需要上下文时为空，避免每次保存都访问全局状态
+    pub fn context_capture(&self) -> proc_macro2::TokenStream {
+        if self.needs_context() {
+            self.current_context()
+        } else {
+            quote! {}
+        }
+    }
+
+    /// 生成绑定 context 为当前上下文的语句；配置 user_name_fn 时，
+    /// 上下文有 user_id 但 user_name 为空则通过该函数解析操作人姓名
+    pub fn current_context(&self) -> proc_macro2::TokenStream {
+        if !self.impersonation && self.user_name_fn.is_none() {
+            return quote! { let context = __auto_field_trait::AutoFieldContext::current_safe(); };
+        }
+
+        let normalize_context = self.normalize_context();
+        quote! {
+            let mut context = __auto_field_trait::AutoFieldContext::current_safe();
+            #normalize_context
+        }
+    }
+
+    /// 生成对可变绑定 context 做代操作归一和姓名解析的语句，未开启 impersonation 和 user_name_fn 时为空
+    pub fn normalize_context(&self) -> proc_macro2::TokenStream {
+        // 代操作：user_id 归一为被代理账号，acting_user_id 归一为实际操作人，未代操作时为 None
+        let impersonation = if self.impersonation {
+            let reset_user_name = if self.user_name_fn.is_some() {
+                quote! { context.user_name = None; }
+            } else {
+                quote! {}
+            };
+            quote! {
+                let acting_user_id = context.acting_user_id.take()
+                    .filter(|acting_user_id| !acting_user_id.is_empty())
+                    .or_else(|| context.user_id.clone());
+                if let Some(on_behalf_of) = context.on_behalf_of.clone().filter(|on_behalf_of| !on_behalf_of.is_empty()) {
+                    if context.user_id.as_ref() != Some(&on_behalf_of) {
+                        context.user_id = Some(on_behalf_of);
+                        #reset_user_name
+                    }
+                }
+                context.acting_user_id = acting_user_id
+                    .filter(|acting_user_id| !acting_user_id.is_empty() && context.user_id.as_ref() != Some(acting_user_id));
+            }
+        } else {
+            quote! {}
+        };
+        let resolve_user_name = match &self.user_name_fn {
+            Some(user_name_fn) => quote! {
+                if !matches!(context.user_name.as_deref(), Some(user_name) if !user_name.is_empty()) {
+                    if let Some(user_id) = context.user_id.as_deref().filter(|user_id| !user_id.is_empty()) {
+                        context.user_name = #user_name_fn(user_id);
+                    }
+                }
+            },
+            None => quote! {},
+        };
+        quote! {
+            #impersonation
+            #resolve_user_name
+        }
+    }
+
+    /// 由生成器取下一个雪花 ID 字符串的表达式，id_worker_from = "tenant" 时在末尾追加两位租户分片号
+    pub fn next_snowflake_id(&self, generator: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if self.id_worker_from == IdWorkerSource::Tenant {
+            let entity_name = self.entity_type();
+            quote! { format!("{}{:02}", #generator.generate(), #entity_name::auto_field_id_shard()) }
+        } else {
+            quote! { #generator.generate().to_string() }
+        }
+    }
+
+    /// 生成为 id 字段赋雪花 ID 的语句，默认直接调用生成器，开启 id_panic_guard 时捕获 panic 并保持 id 不变
+    pub fn snowflake_id_assign(
+        &self,
+        generator: &proc_macro2::TokenStream,
+        target: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let id = self.field("id");
+        let next_id = self.next_snowflake_id(generator);
+        if self.id_panic_guard {
+            quote! {
+                if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #next_id)) {
+                    #target.#id = sea_orm::ActiveValue::Set(id.into());
+                }
+            }
+        } else {
+            quote! {
+                #target.#id = sea_orm::ActiveValue::Set(#next_id.into());
+            }
+        }
+    }
+
+    /// 实体类型，SeaORM 的 DeriveEntityModel 生成的名称是 Entity
+    pub fn entity_type(&self) -> proc_macro2::TokenStream {
+        match &self.entity {
+            Some(path) => quote! { #path },
+            None => quote! { Entity },
+        }
+    }
+
+    /// ActiveModel 类型，SeaORM 的 DeriveEntityModel 生成的名称是 ActiveModel
+    pub fn active_model_type(&self) -> proc_macro2::TokenStream {
+        match &self.active_model {
+            Some(path) => quote! { #path },
+            None => quote! { ActiveModel },
+        }
+    }
+
+    /// 列枚举类型，SeaORM 的 DeriveEntityModel 生成的名称是 Column
+    pub fn column_type(&self) -> proc_macro2::TokenStream {
+        match &self.column_enum {
+            Some(path) => quote! { #path },
+            None => quote! { Column },
+        }
+    }
+
+    /// 为生成代码自行执行的语句附加 SQL 注释，便于在慢查询日志中定位调用方：
+    /// 注释放在构建好的 Statement 的 SQL 之前；未启用 sql_comment 时原样返回
+    pub fn sql_comment_statement(&self, op: &str, statement: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if !self.sql_comment {
+            return statement;
+        }
+        let comment = self.sql_comment_text(op);
+        quote! {
+            {
+                let mut statement: sea_orm::Statement = #statement;
+                statement.sql = format!("{} {}", #comment, statement.sql);
+                statement
+            }
+        }
+    }
+
+    /// 为返回给调用方的查询（Select 等）附加 SQL 注释。这类查询由调用方执行，拿不到最终的 Statement，
+    /// 而 SeaORM 的语句构建器没有注释接口，注释只能放在恒真条件 1 = 1 之后作为 WHERE 的一部分；
+    /// 未启用 sql_comment 时原样返回
+    pub fn with_sql_comment(&self, op: &str, query: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if !self.sql_comment {
+            return query;
+        }
+        let comment = self.sql_comment_text(op);
+        quote! {
+            sea_orm::QueryFilter::filter(
+                #query,
+                sea_orm::sea_query::Expr::cust(format!("1 = 1 {}", #comment))
+            )
+        }
+    }
+
+    /// SQL 注释文本 `/* entity=表名 op=操作 */`，表名中的 */ 会被去掉
+    fn sql_comment_text(&self, op: &str) -> proc_macro2::TokenStream {
+        let entity_name = self.entity_type();
+        quote! {
+            format!(
+                "/* entity={} op={} */",
+                sea_orm::EntityName::table_name(&<#entity_name as ::std::default::Default>::default()).replace("*/", ""),
+                #op
+            )
+        }
+    }
+
+    /// 生成写入批量操作日志的语句，未配置 ops_log 时为空；condition 为 JSON 字符串表达式
+    pub fn ops_log_call(
+        &self,
+        operation: &str,
+        affected: proc_macro2::TokenStream,
+        condition: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if self.ops_log.is_none() {
+            return quote! {};
+        }
+        quote! {
+            Self::auto_field_log_operation(db, #operation, #affected, #condition).await?;
+        }
+    }
+
+    /// 生成获取当前时间的表达式
+    pub fn now_expr(&self) -> proc_macro2::TokenStream {
+        let now = match &self.clock_fn {
+            Some(clock_fn) => quote! { #clock_fn().naive_utc() },
+            None => quote! { chrono::Utc::now().naive_utc() },
+        };
+        match self.timestamp_precision {
+            Some(precision) => {
+                let digits = precision.subsec_digits();
+                quote! { chrono::SubsecRound::trunc_subsecs(#now, #digits) }
+            }
+            None => now,
+        }
+    }
+
+    /// 由宏自动维护的列名（按数据库列名）
+    pub fn managed_columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        if self.snowflake_id {
+            columns.push("id");
+        }
+        if self.timestamps {
+            columns.extend(["create_time", "update_time"]);
+        }
+        if self.audit {
+            columns.extend(["create_by", "create_id", "update_by", "update_id"]);
+        }
+        if self.impersonation {
+            columns.extend(["create_proxy_id", "update_proxy_id"]);
+        }
+        if self.tenant {
+            columns.extend(["tenant_id", "tenant_name"]);
+        }
+        if self.version {
+            columns.push("version");
+        }
+        if self.soft_delete {
+            columns.push("delete_flag");
+        }
+        if self.delete_time {
+            columns.push("delete_time");
+        }
+        if self.ttl_seconds.is_some() {
+            columns.push("expire_time");
+        }
+        if let Some(hash) = &self.hash {
+            columns.push(hash.into.as_str());
+        }
+        columns
+    }
+
+    /// 插入时由其他列派生的业务列（排序、slug、拼音、同步列），调用方可以赋值，未赋值时自动填充
+    pub fn derived_columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        if let Some(sort_column) = &self.sort_column {
+            columns.push(sort_column.as_str());
+        }
+        if let Some(slug) = &self.slug {
+            columns.push(slug.into.as_str());
+        }
+        for pinyin in &self.pinyin {
+            columns.extend(pinyin.full.as_deref());
+            columns.extend(pinyin.initials.as_deref());
+        }
+        columns.extend(self.sync.iter().map(|sync| sync.into.as_str()));
+        columns
+    }
+
+    /// 插入时的快照列：创建人姓名和租户名称，插入后不再改动（denormalize_snapshot）
+    pub fn snapshot_columns(&self) -> Vec<&str> {
+        if !self.denormalize_snapshot {
+            return Vec::new();
+        }
+        let mut columns = Vec::new();
+        if self.audit {
+            columns.push("create_by");
+        }
+        if self.tenant {
+            columns.push("tenant_name");
+        }
+        columns
+    }
+
+    /// 不对外导出的内部受管列：删除标记、租户、版本号和行哈希
+    pub fn internal_columns(&self) -> Vec<&str> {
+        let hash_column = self.hash.as_ref().map(|hash| hash.into.as_str());
+        self.managed_columns()
+            .into_iter()
+            .filter(|column| matches!(*column, "delete_flag" | "tenant_id" | "tenant_name" | "version") || Some(*column) == hash_column)
+            .collect()
+    }
+
+    /// 插入时由生成代码填充的受管列，更新审计列、只在代操作时填充的列、软删除时才写入的 delete_time 和数据库维护的时间戳除外
+    pub fn insert_filled_columns(&self) -> Vec<&str> {
+        self.managed_columns()
+            .into_iter()
+            .filter(|column| !matches!(*column, "update_by" | "update_id" | "create_proxy_id" | "update_proxy_id" | "delete_time"))
+            .filter(|column| self.fills_timestamps() || !matches!(*column, "create_time" | "update_time"))
+            .collect()
+    }
+
+    /// 生成的查询方法及其过滤列，按 (方法名, 过滤列) 列出
+    pub fn query_method_filters(&self) -> Vec<(&'static str, Vec<&'static str>)> {
+        let scope: Vec<&'static str> = if self.soft_delete { vec!["delete_flag"] } else { Vec::new() };
+        let with_scope = |column: &'static str| {
+            let mut filters = vec![column];
+            filters.extend(scope.iter().copied());
+            filters
+        };
+
+        let mut methods = vec![("find_not_deleted", scope.clone())];
+        if self.tenant {
+            methods.push(("find_by_tenant_id", with_scope("tenant_id")));
+        }
+        if self.audit {
+            methods.push(("find_by_creator_id", with_scope("create_id")));
+            methods.push(("find_by_creator_name", with_scope("create_by")));
+        }
+        if self.ttl_seconds.is_some() {
+            methods.push(("find_not_expired", with_scope("expire_time")));
+        }
+        methods
+    }
+}
+
+/// 字段级插入默认值
+#[derive(Debug, Clone)]
+enum FieldDefault {
+    /// #[auto_field(default = ...)]，字面量默认值
+    Lit(Lit),
+    /// #[auto_field(default_expr = "...")]，每次插入时求值的表达式
+    Expr(Expr),
+    /// #[auto_field(default_empty)]，可空文本列插入时填空字符串而不是 NULL
+    Empty,
+}
+
+/// 字段级 #[auto_field(...)] 配置
+#[derive(Debug, Clone, Default)]
+struct FieldConfig {
+    pub default: Option<FieldDefault>,
+    /// 保存时加密，#[auto_field(encrypt)]
+    pub encrypt: bool,
+    /// 脱敏方式，#[auto_field(mask = "phone")]
+    pub mask: Option<MaskKind>,
+    /// 保存前校验规则，#[auto_field(validate(non_empty, max_len = 64))]
+    pub validate: Option<ValidateRules>,
+    /// 保存前去掉首尾空白，#[auto_field(trim)]
+    pub trim: bool,
+    /// 保存前转为小写，#[auto_field(lowercase)]
+    pub lowercase: bool,
+    /// 导出时的表头，#[auto_field(export_header = "用户名")]，未设置时为数据库列名
+    pub export_header: Option<String>,
+    /// 导出时跳过该列，#[auto_field(export_skip)]
+    pub export_skip: bool,
+    /// 个人信息列，匿名化时清空或替换，#[auto_field(pii)] / #[auto_field(pii = "deleted user")]
+    pub pii: bool,
+    /// 匿名化时写入的替换值，未设置时清空为 NULL
+    pub pii_replacement: Option<syn::LitStr>,
+}
+
+/// 字段校验规则，长度按字符数计算
+#[derive(Debug, Clone, Default)]
+struct ValidateRules {
+    /// 不能为空字符串或 None
+    pub non_empty: bool,
+    /// 最小长度
+    pub min_len: Option<usize>,
+    /// 最大长度
+    pub max_len: Option<usize>,
+}
+
+impl ValidateRules {
+    /// 从 validate(...) 列表中解析规则
+    fn from_meta_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut rules = Self::default();
+        let nested = list.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)?;
+        for meta in nested {
+            match &meta {
+                Meta::Path(path) if path.is_ident("non_empty") => rules.non_empty = true,
+                Meta::NameValue(name_value) if name_value.path.is_ident("min_len") || name_value.path.is_ident("max_len") => {
+                    let value = usize::try_from(parse_int_value(&name_value.value)?)
+                        .map_err(|_| syn::Error::new_spanned(&name_value.value, "Expected a non-negative length"))?;
+                    if name_value.path.is_ident("min_len") {
+                        rules.min_len = Some(value);
+                    } else {
+                        rules.max_len = Some(value);
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &meta,
+                        "Unknown validate rule, expected one of: non_empty, min_len = <n>, max_len = <n>"
+                    ));
+                }
+            }
+        }
+        if let (Some(min_len), Some(max_len)) = (rules.min_len, rules.max_len) {
+            if min_len > max_len {
+                return Err(syn::Error::new_spanned(list, "validate min_len must not exceed max_len"));
+            }
+        }
+        Ok(rules)
+    }
+}
+
+/// 字段脱敏方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MaskKind {
+    /// 手机号，保留前 3 位和后 4 位：138****1234
+    Phone,
+    /// 证件号，保留前 4 位和后 4 位
+    IdCard,
+    /// 银行卡号，保留后 4 位
+    BankCard,
+    /// 邮箱，保留用户名首字符和域名：a***@example.com
+    Email,
+    /// 姓名，保留首字符：张*
+    Name,
+    /// 全部替换为 *
+    Full,
+}
+
+impl MaskKind {
+    /// 从字符串字面量解析脱敏方式
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        match parse_str_value(expr)?.as_str() {
+            "phone" => Ok(Self::Phone),
+            "id_card" => Ok(Self::IdCard),
+            "bank_card" => Ok(Self::BankCard),
+            "email" => Ok(Self::Email),
+            "name" => Ok(Self::Name),
+            "full" => Ok(Self::Full),
+            other => Err(syn::Error::new_spanned(
+                expr,
+                format!(
+                    "Unknown mask: {}, expected one of \"phone\", \"id_card\", \"bank_card\", \"email\", \"name\", \"full\"",
+                    other
+                )
+            )),
+        }
+    }
+
+    /// 保留的首尾字符数，邮箱只作用于用户名部分
+    fn keep(self) -> (usize, usize) {
+        match self {
+            Self::Phone => (3, 4),
+            Self::IdCard => (4, 4),
+            Self::BankCard => (0, 4),
+            Self::Email | Self::Name => (1, 0),
+            Self::Full => (0, 0),
+        }
+    }
+}
+
+/// 字段级 #[auto_field(...)] 支持的配置项
+const FIELD_CONFIG_KEYS: &[&str] = &[
+    "default", "default_expr", "default_empty", "encrypt", "mask", "validate", "trim", "lowercase",
+    "export_header", "export_skip", "pii",
+];
+
+/// 字段级未知配置项的错误，列出支持的配置项
+fn unknown_field_key(path: &syn::Path, key: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        path,
+        format!(
+            "Unknown auto_field field configuration key: {}, expected one of: {}",
+            key,
+            FIELD_CONFIG_KEYS.join(", ")
+        )
+    )
+}
+
+impl FieldConfig {
+    /// 设置默认值，default、default_expr、default_empty 只能配置其一
+    fn set_default(&mut self, default: FieldDefault, path: &syn::Path) -> syn::Result<()> {
+        if self.default.is_some() {
+            return Err(syn::Error::new_spanned(
+                path,
+                "default, default_expr and default_empty cannot be combined on the same field"
+            ));
+        }
+        self.default = Some(default);
+        Ok(())
+    }
+
+    /// 从字段属性中解析配置
+    pub fn from_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut config = Self::default();
+
+        for attr in attrs {
+            if !attr.path().is_ident("auto_field") {
+                continue;
+            }
+            let nested = attr.parse_args_with(
+                syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated
+            )?;
+
+            for meta in nested {
+                match meta {
+                    Meta::NameValue(name_value) => {
+                        let key = name_value.path.get_ident()
+                            .ok_or_else(|| syn::Error::new_spanned(&name_value.path, "Expected identifier"))?
+                            .to_string();
+
+                        match key.as_str() {
+                            "mask" => {
+                                config.mask = Some(MaskKind::from_expr(&name_value.value)?);
+                                continue;
+                            }
+                            "encrypt" => {
+                                config.encrypt = parse_bool_value(&name_value.value)?;
+                                continue;
+                            }
+                            "trim" => {
+                                config.trim = parse_bool_value(&name_value.value)?;
+                                continue;
+                            }
+                            "lowercase" => {
+                                config.lowercase = parse_bool_value(&name_value.value)?;
+                                continue;
+                            }
+                            "export_header" => {
+                                config.export_header = Some(parse_str_value(&name_value.value)?);
+                                continue;
+                            }
+                            "export_skip" => {
+                                config.export_skip = parse_bool_value(&name_value.value)?;
+                                continue;
+                            }
+                            "pii" => {
+                                let Expr::Lit(syn::ExprLit { lit: Lit::Str(replacement), .. }) = &name_value.value else {
+                                    return Err(syn::Error::new_spanned(&name_value.value, "Expected a string replacement value"));
+                                };
+                                config.pii = true;
+                                config.pii_replacement = Some(replacement.clone());
+                                continue;
+                            }
+                            _ => {}
+                        }
+
+                        let default = match key.as_str() {
+                            "default" => match &name_value.value {
+                                Expr::Lit(expr_lit) => FieldDefault::Lit(expr_lit.lit.clone()),
+                                other => {
+                                    return Err(syn::Error::new_spanned(other, "Expected literal default value"));
+                                }
+                            },
+                            "default_expr" => {
+                                let value = parse_str_value(&name_value.value)?;
+                                FieldDefault::Expr(syn::parse_str::<Expr>(&value).map_err(|_| syn::Error::new_spanned(
+                                    &name_value.value,
+                                    format!("Expected an expression, found \"{}\"", value)
+                                ))?)
+                            }
+                            "default_empty" => {
+                                if !parse_bool_value(&name_value.value)? {
+                                    continue;
+                                }
+                                FieldDefault::Empty
+                            }
+                            _ => return Err(unknown_field_key(&name_value.path, &key)),
+                        };
+                        config.set_default(default, &name_value.path)?;
+                    }
+                    Meta::Path(path) => {
+                        let key = path.get_ident()
+                            .ok_or_else(|| syn::Error::new_spanned(&path, "Expected identifier"))?
+                            .to_string();
+
+                        match key.as_str() {
+                            "encrypt" => config.encrypt = true,
+                            "trim" => config.trim = true,
+                            "lowercase" => config.lowercase = true,
+                            "export_skip" => config.export_skip = true,
+                            "pii" => config.pii = true,
+                            "default_empty" => config.set_default(FieldDefault::Empty, &path)?,
+                            "default" | "default_expr" | "mask" | "validate" | "export_header" => {
+                                return Err(syn::Error::new_spanned(
+                                    &path,
+                                    format!("auto_field field configuration key {} requires a value", key)
+                                ));
+                            }
+                            _ => return Err(unknown_field_key(&path, &key)),
+                        }
+                    }
+                    Meta::List(list) if list.path.is_ident("validate") => {
+                        config.validate = Some(ValidateRules::from_meta_list(&list)?);
+                    }
+                    Meta::List(list) => {
+                        return Err(syn::Error::new_spanned(
+                            &list,
+                            "Expected key = value or key format in field-level auto_field attribute"
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// 实体字段
+#[derive(Debug, Clone)]
+struct EntityField {
+    pub ident: syn::Ident,
+    /// 字段名，raw identifier 已去掉 r# 前缀，如 r#type -> type
+    pub name: String,
+    /// 数据库列名，取自 #[sea_orm(column_name = "...")]，未设置时与字段名相同
+    pub column: String,
+    pub ty: syn::Type,
+    pub config: FieldConfig,
+}
+
+/// 实体字段集合
+#[derive(Debug, Clone, Default)]
+struct EntityFields {
+    pub fields: Vec<EntityField>,
+}
+
+/// 受管字段期望的值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpectedType {
+    /// String
+    String,
+    /// Option<String>
+    OptionString,
+    /// Option<DateTime>（chrono::NaiveDateTime）
+    OptionDateTime,
+    /// Option<NaiveDateTime / DateTimeUtc / DateTimeWithTimeZone> 或 Option<i64> 毫秒
+    OptionTimestamp,
+    /// Option<整数>
+    OptionInteger,
+    /// Option<整数> 或 Option<bool>
+    OptionIntegerOrBool,
+}
+
+impl ExpectedType {
+    /// 类型是否符合期望
+    fn matches(self, ty: &syn::Type) -> bool {
+        let inner = option_inner_type(ty);
+        match self {
+            Self::String => inner.is_none() && is_string_like_type(ty),
+            Self::OptionString => inner.is_some_and(is_string_like_type),
+            Self::OptionDateTime => inner.is_some_and(|inner| {
+                type_last_segment(inner).is_some_and(|segment| {
+                    matches!(segment.arguments, syn::PathArguments::None)
+                        && (segment.ident == "DateTime" || segment.ident == "NaiveDateTime")
+                })
+            }),
+            Self::OptionTimestamp => inner.and_then(TimestampStorage::of).is_some(),
+            Self::OptionInteger => inner.is_some_and(is_integer_type),
+            Self::OptionIntegerOrBool => inner.is_some_and(|inner| is_integer_type(inner) || is_bool_type(inner)),
+        }
+    }
+
+    /// 用于错误信息的类型描述
+    fn describe(self) -> &'static str {
+        match self {
+            Self::String => "String (or SmolStr / Cow<str>)",
+            Self::OptionString => "Option<String> (or SmolStr / Cow<str>)",
+            Self::OptionDateTime => "Option<DateTime> (chrono::NaiveDateTime)",
+            Self::OptionTimestamp => "Option<DateTime> (NaiveDateTime, DateTimeUtc or DateTimeWithTimeZone) or Option<i64> epoch millis",
+            Self::OptionInteger => "Option<i16/i32/i64>",
+            Self::OptionIntegerOrBool => "Option<i16/i32/i64> or Option<bool>",
+        }
+    }
+}
+
+impl EntityFields {
+    /// 校验已启用功能所需的受管字段存在且类型正确
+    pub fn validate_managed(&self, config: &AutoFieldConfig, struct_name: &syn::Ident) -> syn::Result<()> {
+        let mut expected = Vec::new();
+        if config.snowflake_id {
+            expected.push(("snowflake_id", "id", ExpectedType::String));
+        }
+        if config.timestamps {
+            expected.push(("timestamps", "create_time", ExpectedType::OptionTimestamp));
+            expected.push(("timestamps", "update_time", ExpectedType::OptionTimestamp));
+        }
+        if config.audit {
+            for column in ["create_by", "create_id", "update_by", "update_id"] {
+                expected.push(("audit", column, ExpectedType::OptionString));
+            }
+        }
+        if config.impersonation {
+            expected.push(("impersonation", "create_proxy_id", ExpectedType::OptionString));
+            expected.push(("impersonation", "update_proxy_id", ExpectedType::OptionString));
+        }
+        if config.tenant {
+            expected.push(("tenant", "tenant_id", ExpectedType::OptionString));
+            expected.push(("tenant", "tenant_name", ExpectedType::OptionString));
+        }
+        if config.version {
+            expected.push(("version", "version", ExpectedType::OptionInteger));
+        }
+        if config.soft_delete {
+            expected.push(("soft_delete", "delete_flag", ExpectedType::OptionIntegerOrBool));
+        }
+        if config.delete_time {
+            expected.push(("delete_time", "delete_time", ExpectedType::OptionDateTime));
+        }
+        if config.ttl_seconds.is_some() {
+            expected.push(("ttl", "expire_time", ExpectedType::OptionDateTime));
+        }
+
+        for (feature, column, expected_type) in expected {
+            let field = self.get(column).ok_or_else(|| syn::Error::new_spanned(
+                struct_name,
+                format!("{} is enabled but the struct has no `{}` field", feature, column)
+            ))?;
+            if !expected_type.matches(&field.ty) {
+                let ty = &field.ty;
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "{} must be {}, found {}",
+                        column,
+                        expected_type.describe(),
+                        quote!(#ty).to_string().replace(' ', "")
+                    )
+                ));
+            }
+        }
+        // create_time 和 update_time 共用同一个填充值，存储类型必须一致
+        if config.timestamps {
+            let storage = |column: &str| self.get(column).and_then(|field| option_inner_type(&field.ty)).and_then(TimestampStorage::of);
+            let storage = (storage("create_time"), storage("update_time"));
+            if storage.0 != storage.1 {
+                return Err(syn::Error::new_spanned(
+                    struct_name,
+                    "create_time and update_time must use the same timestamp type"
+                ));
+            }
+            if storage.0 == Some(TimestampStorage::Millis) && config.timestamps_source == TimestampSource::Database {
+                return Err(syn::Error::new_spanned(
+                    struct_name,
+                    "timestamps_source = \"database\" requires datetime columns, epoch millis columns are filled by the application only"
+                ));
+            }
+        }
+        // 历史表按主键读取旧行
+        if config.archive_to.is_some() && self.get("id").is_none() {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "archive_to is enabled but the struct has no `id` field"
+            ));
+        }
+        // 索引文档按主键同步
+        if config.search_sync_fn.is_some() && self.get("id").is_none() {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "search_sync_fn is enabled but the struct has no `id` field"
+            ));
+        }
+        self.validate_state_pairs(config, struct_name)
+    }
+
+    /// state 与 state_name 同时存在时，两者的默认值和软删除写入值必须成对配置，
+    /// 否则 state_name 会保留旧值，与 state 不一致。state_name 由 sync 派生时不校验
+    fn validate_state_pairs(&self, config: &AutoFieldConfig, struct_name: &syn::Ident) -> syn::Result<()> {
+        let (Some(state), Some(state_name)) = (self.get("state"), self.get("state_name")) else {
+            return Ok(());
+        };
+        if config.sync.iter().any(|sync| state_name.column == sync.into || state_name.name == sync.into) {
+            return Ok(());
+        }
+
+        match (&state.config.default, &state_name.config.default) {
+            (Some(_), None) => {
+                return Err(syn::Error::new_spanned(
+                    &state_name.ident,
+                    "`state` has a default but `state_name` does not, configure both defaults together"
+                ));
+            }
+            (None, Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    &state.ident,
+                    "`state_name` has a default but `state` does not, configure both defaults together"
+                ));
+            }
+            _ => {}
+        }
+
+        let Some(delete_state) = &config.delete_state else {
+            return Ok(());
+        };
+        if delete_state.state_name.is_none() {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "delete_sets_state sets `state` but not `state_name`, use delete_sets_state(state = \"...\", state_name = \"...\")"
+            ));
+        }
+        if delete_state.restore_state.is_some() != delete_state.restore_state_name.is_some() {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "delete_sets_state requires restore_state and restore_state_name to be configured together"
+            ));
+        }
+        Ok(())
+    }
+
+    /// 按列名查找字段，优先匹配 #[sea_orm(column_name = "...")]，其次匹配字段名
+    pub fn get(&self, name: &str) -> Option<&EntityField> {
+        self.fields
+            .iter()
+            .find(|field| field.column == name)
+            .or_else(|| self.fields.iter().find(|field| field.name == name))
+    }
+
+    /// 从结构体字段中收集字段信息，元组结构体和单元结构体没有可识别的列，直接报错
+    pub fn from_fields(fields: &syn::Fields, struct_name: &syn::Ident) -> syn::Result<Self> {
+        let syn::Fields::Named(named) = fields else {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "AutoField requires named fields, use #[auto_field(skip_all)] to generate nothing"
+            ));
+        };
+
+        let mut entity_fields = Self::default();
+        for field in &named.named {
+            let Some(ident) = field.ident.clone() else {
+                continue;
+            };
+            let name = ident.unraw().to_string();
+            entity_fields.fields.push(EntityField {
+                column: parse_column_name(&field.attrs).unwrap_or_else(|| name.clone()),
+                name,
+                ident,
+                ty: field.ty.clone(),
+                config: FieldConfig::from_attributes(&field.attrs)?,
+            });
+        }
+        Ok(entity_fields)
+    }
+}
+
+/// 若类型为 Option<T>，返回 T
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 将 snake_case 列名转换为 SeaORM Column 枚举的变体名，如 display_order -> DisplayOrder
+fn column_variant(column: &str, span: proc_macro2::Span) -> syn::Ident {
+    let variant: String = column
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    syn::Ident::new(&variant, span)
+}
+
+/// 返回类型路径的最后一段，如 Option<String> 返回 Option<String> 段
+fn type_last_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// 判断类型是否为整数
+fn is_integer_type(ty: &syn::Type) -> bool {
+    type_last_segment(ty).is_some_and(|segment| {
+        ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"]
+            .iter()
+            .any(|name| segment.ident == name)
+    })
+}
+
+/// 判断类型是否为 bool
+fn is_bool_type(ty: &syn::Type) -> bool {
+    type_last_segment(ty).is_some_and(|segment| segment.ident == "bool")
+}
+
+/// 判断类型是否为 String
+fn is_string_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last()
+            .is_some_and(|segment| segment.ident == "String"),
+        _ => false,
+    }
+}
+
+/// 判断类型是否为可替代 String 的字符串类型：String、SmolStr 或 Cow<str>。
+/// 生成代码对这些类型统一通过 From / Into 转换，不直接构造 String
+fn is_string_like_type(ty: &syn::Type) -> bool {
+    is_string_type(ty)
+        || type_last_segment(ty).is_some_and(|segment| {
+            segment.ident == "SmolStr"
+                || (segment.ident == "Cow"
+                    && matches!(&segment.arguments, syn::PathArguments::AngleBracketed(args)
+                        if args.args.iter().any(|arg| matches!(arg, syn::GenericArgument::Type(syn::Type::Path(path)) if path.path.is_ident("str")))))
+        })
+}
+
+/// 将字符串字面量转换为列的取值：字符串列直接使用，布尔列接受 "0" / "1"，其他列按表达式解析，如 "0"
+fn str_value_tokens(lit_str: &syn::LitStr, value_ty: &syn::Type) -> syn::Result<proc_macro2::TokenStream> {
+    if is_string_type(value_ty) {
+        Ok(quote! { ::std::string::String::from(#lit_str) })
+    } else if is_string_like_type(value_ty) {
+        Ok(quote! { <#value_ty as ::std::convert::From<&str>>::from(#lit_str) })
+    } else if is_bool_type(value_ty) && matches!(lit_str.value().as_str(), "0" | "1") {
+        // 布尔列沿用整数写法，"0" / "1" 对应 false / true
+        let value = lit_str.value() == "1";
+        Ok(quote! { #value })
+    } else {
+        let expr = lit_str.parse::<Expr>()?;
+        Ok(quote! { #expr })
+    }
+}
+
+/// 生成字段级默认值的插入填充逻辑，target 为 self 或 active_model
+fn generate_field_default_fills(
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut fills = Vec::new();
+    for field in &fields.fields {
+        let Some(default) = &field.config.default else {
+            continue;
+        };
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let value_ty = option_inner_type(ty).unwrap_or(ty);
+
+        let value = match default {
+            FieldDefault::Lit(Lit::Str(lit_str)) => str_value_tokens(lit_str, value_ty)?,
+            FieldDefault::Lit(lit) => quote! { #lit },
+            FieldDefault::Expr(expr) => quote! { #expr },
+            FieldDefault::Empty => {
+                if option_inner_type(ty).is_none() || !is_string_like_type(value_ty) {
+                    return Err(syn::Error::new_spanned(ty, "default_empty requires an Option<String> field"));
+                }
+                str_value_tokens(&syn::LitStr::new("", ident.span()), value_ty)?
+            }
+        };
+
+        fills.push(if option_inner_type(ty).is_some() {
+            quote! {
+                if should_fill_field!(#target.#ident) {
+                    #target.#ident = sea_orm::ActiveValue::Set(Some(#value));
+                }
+            }
+        } else {
+            quote! {
+                if should_fill_field!(#target.#ident, #ty) {
+                    #target.#ident = sea_orm::ActiveValue::Set(#value);
+                }
+            }
+        });
+    }
+    Ok(fills)
+}
+
+/// 支持 key = cfg(...) 条件启用的配置项
+const CONDITIONAL_KEYS: &[&str] = &[
+    "snowflake_id", "timestamps", "audit", "tenant", "version", "soft_delete",
+    "audit_required", "gen_factory", "gen_builder", "active_model_defaults", "sqlite_compat",
+];
+
+/// 同一实体最多支持的条件配置项数量，每个条件项会使生成的实现数量翻倍
+const MAX_CONDITIONAL_KEYS: usize = 3;
+
+/// 解析 cfg(...) 形式的取值，返回括号内的 cfg 谓词
+fn parse_cfg_value(expr: &Expr) -> Option<proc_macro2::TokenStream> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Expr::Path(func) = call.func.as_ref() else {
+        return None;
+    };
+    if !func.path.is_ident("cfg") || call.args.len() != 1 {
+        return None;
+    }
+    let predicate = &call.args;
+    Some(quote! { #predicate })
+}
+
+/// 解析布尔值
+fn parse_bool_value(expr: &Expr) -> syn::Result<bool> {
+    match expr {
         Expr::Lit(expr_lit) => {
             match &expr_lit.lit {
                 Lit::Bool(lit_bool) => Ok(lit_bool.value),
                 _ => Err(syn::Error::new_spanned(expr, "Expected boolean value")),
             }
         }
-        _ => Err(syn::Error::new_spanned(expr, "Expected boolean literal")),
+        _ => Err(syn::Error::new_spanned(expr, "Expected boolean literal")),
+    }
+}
+
+/// 解析字符串值
+fn parse_str_value(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(expr_lit) => {
+            match &expr_lit.lit {
+                Lit::Str(lit_str) => Ok(lit_str.value()),
+                _ => Err(syn::Error::new_spanned(expr, "Expected string value")),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(expr, "Expected string literal")),
+    }
+}
+
+/// 解析整数值
+fn parse_int_value(expr: &Expr) -> syn::Result<i64> {
+    match expr {
+        Expr::Lit(expr_lit) => {
+            match &expr_lit.lit {
+                Lit::Int(lit_int) => lit_int.base10_parse(),
+                _ => Err(syn::Error::new_spanned(expr, "Expected integer value")),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(expr, "Expected integer literal")),
+    }
+}
+
+/// 解析嵌套列表中的 key = value 项，如 slug(from = "title")
+fn parse_nested_name_values(list: &syn::MetaList) -> syn::Result<Vec<syn::MetaNameValue>> {
+    list.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)?
+        .into_iter()
+        .map(|meta| match meta {
+            Meta::NameValue(name_value) => Ok(name_value),
+            other => Err(syn::Error::new_spanned(other, "Expected key = value format")),
+        })
+        .collect()
+}
+
+/// 解析字符串数组，如 ["col_a", "col_b"]；单个字符串视为只有一项
+fn parse_str_list_value(expr: &Expr) -> syn::Result<Vec<String>> {
+    match expr {
+        Expr::Array(array) => array.elems.iter().map(parse_str_value).collect(),
+        _ => Ok(vec![parse_str_value(expr)?]),
+    }
+}
+
+/// 解析字符串形式的路径，如 "my_app::clock::now"
+fn parse_path_value(expr: &Expr) -> syn::Result<syn::Path> {
+    let value = parse_str_value(expr)?;
+    syn::parse_str::<syn::Path>(&value)
+        .map_err(|_| syn::Error::new_spanned(expr, format!("Expected a path, found \"{}\"", value)))
+}
+
+/// 解析时长字符串，如 "30d"、"12h"、"15m"、"90s"，返回秒数
+fn parse_duration_value(expr: &Expr) -> syn::Result<i64> {
+    let value = parse_str_value(expr)?;
+    let invalid = || syn::Error::new_spanned(
+        expr,
+        format!("Invalid duration \"{}\", expected a number followed by s, m, h or d (e.g. \"30d\")", value)
+    );
+    let split = value.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    amount.checked_mul(unit_seconds).filter(|seconds| *seconds > 0).ok_or_else(invalid)
+}
+
+/// 从 #[sea_orm(table_name = "...")] 属性中读取表名
+fn parse_table_name(attrs: &[Attribute]) -> Option<String> {
+    for attr in attrs {
+        if !attr.path().is_ident("sea_orm") {
+            continue;
+        }
+        let nested = attr
+            .parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)
+            .ok()?;
+        for meta in nested {
+            if let Meta::NameValue(name_value) = meta {
+                if name_value.path.is_ident("table_name") {
+                    return parse_str_value(&name_value.value).ok();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 从字段的 #[sea_orm(column_name = "...")] 中读取列名
+fn parse_column_name(attrs: &[Attribute]) -> Option<String> {
+    for attr in attrs {
+        if !attr.path().is_ident("sea_orm") {
+            continue;
+        }
+        let Ok(nested) = attr.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated) else {
+            continue;
+        };
+        for meta in nested {
+            if let Meta::NameValue(name_value) = meta {
+                if name_value.path.is_ident("column_name") {
+                    return parse_str_value(&name_value.value).ok();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 生成将 auto_field_trait 中的 AutoFieldError 转换为 DbErr 的表达式，如 auto_field_error(quote! { SoftDeleteDisabled { .. } })
+fn auto_field_error(error: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        sea_orm::DbErr::from(__auto_field_trait::AutoFieldError::#error)
+    }
+}
+
+/// 将编译期产物写入 OUT_DIR/auto_field/<file_name>
+fn write_out_dir_artifact(file_name: &str, contents: &str) -> syn::Result<()> {
+    let out_dir = std::env::var("OUT_DIR").map_err(|_| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        "OUT_DIR is not set, add a build script (build.rs) to the crate to emit auto_field artifacts"
+    ))?;
+    let dir = std::path::Path::new(&out_dir).join("auto_field");
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(dir.join(file_name), contents))
+        .map_err(|err| syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Failed to write auto_field artifact {}: {}", file_name, err)
+        ))
+}
+
+/// 生成运行时库别名 __auto_field_trait 的 use 语句，展开结果统一通过它访问 auto_field_trait 模块，
+/// 使 trait crate 的路径只在这一处决定：trait_path 优先，其次按使用方 Cargo.toml 中的依赖重命名推断
+fn generate_runtime_alias(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let root = match &config.trait_path {
+        Some(path) => quote! { #path },
+        None => dependency_root("auto_field_trait").unwrap_or_else(|| quote! { ::auto_field_trait }),
+    };
+    quote! {
+        #[allow(unused_imports)]
+        use #root::auto_field_trait as __auto_field_trait;
+    }
+}
+
+/// 本 crate 在使用方中的路径，生成代码通过它引用 AutoField 和 __auto_field_expand，依赖被重命名时同样可用
+fn macros_crate_root() -> proc_macro2::TokenStream {
+    dependency_root("auto_field_macros").unwrap_or_else(|| quote! { ::auto_field_macros })
+}
+
+/// 按使用方的 Cargo.toml 解析 package 在生成代码中的路径，支持重命名、target 依赖和 workspace 继承；
+/// 找不到该依赖时返回 None，由调用方回退到默认 crate 名
+fn dependency_root(package: &str) -> Option<proc_macro2::TokenStream> {
+    match proc_macro_crate::crate_name(package).ok()? {
+        proc_macro_crate::FoundCrate::Itself => Some(quote! { crate }),
+        proc_macro_crate::FoundCrate::Name(name) => {
+            let name = syn::Ident::new(&name, proc_macro2::Span::call_site());
+            Some(quote! { ::#name })
+        }
+    }
+}
+
+
+
+/// AutoField 派生宏
+#[proc_macro_derive(AutoField, attributes(auto_field))]
+pub fn derive_auto_field(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_auto_field(input, Vec::new()).into()
+}
+
+/// 预设回调：auto_field_preset! 生成的 macro_rules! 注入预设配置后调用，
+/// 输入为 `("已展开的预设", ...) 派生输入`，仅供生成代码使用
+#[doc(hidden)]
+#[proc_macro]
+pub fn __auto_field_expand(input: TokenStream) -> TokenStream {
+    let PresetExpansion { visited, input } = parse_macro_input!(input as PresetExpansion);
+    expand_auto_field(input, visited).into()
+}
+
+/// 展开 AutoField。配置中的 extends 逐个交给对应预设的 macro_rules!，由它把预设配置插到最前面后
+/// 回调 __auto_field_expand，所有 extends 都处理完后才生成实现；visited 为已展开的预设，用于检测循环引用
+fn expand_auto_field(mut input: DeriveInput, visited: Vec<syn::LitStr>) -> proc_macro2::TokenStream {
+    let preset = match take_extends(&mut input.attrs) {
+        Ok(preset) => preset,
+        Err(err) => return derive_error(&input, err),
+    };
+    let Some(preset) = preset else {
+        return generate_auto_field_impl(&input).unwrap_or_else(|err| derive_error(&input, err));
+    };
+
+    if visited.iter().any(|name| name.value() == preset.value()) {
+        let chain: Vec<String> = visited.iter().map(syn::LitStr::value).collect();
+        let err = syn::Error::new(
+            preset.span(),
+            format!("auto_field preset cycle: {} -> {}", chain.join(" -> "), preset.value())
+        );
+        return derive_error(&input, err);
+    }
+    let path: syn::Path = match preset.parse() {
+        Ok(path) => path,
+        Err(_) => {
+            let err = syn::Error::new(preset.span(), "extends expects a preset name or path, e.g. \"crate::presets::Base\"");
+            return derive_error(&input, err);
+        }
+    };
+    let root = macros_crate_root();
+    quote! {
+        #path! { [#root::__auto_field_expand] (#(#visited)* #preset) #input }
+    }
+}
+
+/// 生成失败时的输出
+fn derive_error(input: &DeriveInput, err: syn::Error) -> proc_macro2::TokenStream {
+    let error = err.to_compile_error();
+    // 输入是具名字段结构体且配置能解析时，附带空的 ActiveModelBehavior 实现，避免缺少该实现引起的级联错误；
+    // 其余情况下无法确定 ActiveModel 的类型，只输出错误
+    let named_struct = matches!(&input.data, syn::Data::Struct(data) if matches!(data.fields, syn::Fields::Named(_)));
+    match AutoFieldConfig::from_attributes(&input.attrs) {
+        Ok(config) if named_struct => {
+            let active_model_name = config.active_model_type();
+            quote! {
+                #error
+                impl sea_orm::ActiveModelBehavior for #active_model_name {}
+            }
+        }
+        _ => error,
+    }
+}
+
+/// 取出第一个 extends 配置并从属性中删除，格式错误的属性留给 from_attributes 报告
+fn take_extends(attrs: &mut [Attribute]) -> syn::Result<Option<syn::LitStr>> {
+    for attr in attrs.iter_mut() {
+        if !attr.path().is_ident("auto_field") {
+            continue;
+        }
+        let Meta::List(meta_list) = &mut attr.meta else {
+            continue;
+        };
+        let Ok(nested) = meta_list.parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated) else {
+            continue;
+        };
+        let Some(index) = nested.iter().position(|meta| meta.path().is_ident("extends")) else {
+            continue;
+        };
+        let mut rest: Vec<Meta> = nested.into_iter().collect();
+        let preset = match rest.remove(index) {
+            Meta::NameValue(syn::MetaNameValue { value: syn::Expr::Lit(syn::ExprLit { lit: Lit::Str(lit), .. }), .. }) => lit,
+            meta => return Err(syn::Error::new_spanned(meta, "extends expects a string, e.g. extends = \"Base\"")),
+        };
+        meta_list.tokens = quote! { #(#rest),* };
+        return Ok(Some(preset));
+    }
+    Ok(None)
+}
+
+/// __auto_field_expand 的输入
+struct PresetExpansion {
+    visited: Vec<syn::LitStr>,
+    input: DeriveInput,
+}
+
+impl syn::parse::Parse for PresetExpansion {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        syn::parenthesized!(content in input);
+        let mut visited = Vec::new();
+        while !content.is_empty() {
+            visited.push(content.parse()?);
+        }
+        Ok(Self { visited, input: input.parse()? })
+    }
+}
+
+/// 为实体生成一组 #[tokio::test]，在 MockDatabase 上检查插入填充、更新填充、保护语义和软删除，
+/// 如 #[cfg(test)] mod tests { auto_field_testgen!(super::user::Entity); }
+///
+/// 检查内容由实体的 #[auto_field(...)] 配置决定，未启用的功能对应的测试直接通过；
+/// 只能在实体所在 crate 的测试构建中使用，需要 tokio（macros、rt）和 sea-orm 的 mock 特性
+#[proc_macro]
+pub fn auto_field_testgen(input: TokenStream) -> TokenStream {
+    let entity = parse_macro_input!(input as syn::Path);
+
+    // user::Entity -> auto_field_user_entity_insert_fill
+    let mut prefix = String::from("auto_field");
+    for segment in &entity.segments {
+        prefix.push('_');
+        for (index, ch) in segment.ident.to_string().trim_start_matches("r#").chars().enumerate() {
+            if ch.is_uppercase() && index > 0 && !prefix.ends_with('_') {
+                prefix.push('_');
+            }
+            prefix.extend(ch.to_lowercase());
+        }
+    }
+    let test_name = |check: &str| syn::Ident::new(&format!("{}_{}", prefix, check), proc_macro2::Span::call_site());
+    let insert_fill = test_name("insert_fill");
+    let update_fill = test_name("update_fill");
+    let protection = test_name("protection");
+    let soft_delete = test_name("soft_delete");
+
+    // 查询结果按需取用，预留足够多的空结果，填充逻辑中的查询（排序号、slug 冲突检查等）都读到空结果
+    let connection = quote! {
+        sea_orm::MockDatabase::new(#entity::auto_field_selftest_backend())
+            .append_query_results(std::iter::repeat_with(Vec::<sea_orm::MockRow>::new).take(32))
+            .append_exec_results(std::iter::repeat_with(sea_orm::MockExecResult::default).take(32))
+            .into_connection()
+    };
+
+    quote! {
+        #[tokio::test]
+        async fn #insert_fill() {
+            #entity::auto_field_selftest_insert_fill(&#connection).await;
+        }
+
+        #[tokio::test]
+        async fn #update_fill() {
+            #entity::auto_field_selftest_update_fill(&#connection).await;
+        }
+
+        #[tokio::test]
+        async fn #protection() {
+            #entity::auto_field_selftest_protection(&#connection).await;
+        }
+
+        #[test]
+        fn #soft_delete() {
+            #entity::auto_field_selftest_soft_delete();
+        }
+    }
+    .into()
+}
+
+/// 为模块内所有实体的 Model 应用同一份 auto_field 配置，
+/// 如 #[auto_field_module(snowflake_id, timestamps, audit)] mod entities { pub mod user { ... } }
+///
+/// 共享配置排在 Model 自身的 #[auto_field(...)] 之前，同名配置以 Model 自身为准；
+/// 未派生 AutoField 的 Model 会自动加上 #[derive(AutoField)]
+#[proc_macro_attribute]
+pub fn auto_field_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = proc_macro2::TokenStream::from(args);
+    let mut module = parse_macro_input!(input as syn::ItemMod);
+
+    match apply_module_config(&args, &mut module) {
+        Ok(()) => quote! { #module }.into(),
+        Err(err) => {
+            let error = err.to_compile_error();
+            quote! {
+                #error
+                #module
+            }
+            .into()
+        }
+    }
+}
+
+/// 校验共享配置并递归应用到模块内的每个 Model
+fn apply_module_config(args: &proc_macro2::TokenStream, module: &mut syn::ItemMod) -> syn::Result<()> {
+    let shared: Attribute = syn::parse_quote! { #[auto_field(#args)] };
+    // 共享配置在这里先解析一次，键名或取值错误只在模块上报告一次，而不是每个 Model 各报一次
+    AutoFieldConfig::from_attributes(std::slice::from_ref(&shared))?;
+
+    let Some((_, items)) = &mut module.content else {
+        return Err(syn::Error::new_spanned(
+            &module.ident,
+            "auto_field_module requires an inline module, `mod name;` contents are not visible to attribute macros"
+        ));
+    };
+    apply_module_items(&shared, items)
+}
+
+fn apply_module_items(shared: &Attribute, items: &mut [syn::Item]) -> syn::Result<()> {
+    for item in items {
+        match item {
+            syn::Item::Mod(module) => match &mut module.content {
+                Some((_, items)) => apply_module_items(shared, items)?,
+                // 外部文件模块的内容在属性宏展开时不可见，静默跳过会让实体悄悄缺少受管字段
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        &module.ident,
+                        format!(
+                            "auto_field_module cannot see the contents of `mod {};`, declare it inline or derive AutoField on its Model",
+                            module.ident
+                        )
+                    ));
+                }
+            },
+            syn::Item::Struct(item_struct) if item_struct.ident == "Model" => {
+                let position = item_struct
+                    .attrs
+                    .iter()
+                    .position(|attr| attr.path().is_ident("auto_field"))
+                    .unwrap_or(item_struct.attrs.len());
+                item_struct.attrs.insert(position, shared.clone());
+                if !derives_auto_field(&item_struct.attrs)? {
+                    let root = macros_crate_root();
+                    item_struct.attrs.insert(0, syn::parse_quote! { #[derive(#root::AutoField)] });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Model 是否已经 #[derive(AutoField)]
+fn derives_auto_field(attrs: &[Attribute]) -> syn::Result<bool> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let paths = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated
+        )?;
+        if paths.iter().any(|path| path.segments.last().is_some_and(|segment| segment.ident == "AutoField")) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 定义具名配置预设，实体通过 #[auto_field(extends = "Name")] 引用，
+/// 如 auto_field_preset! { BaseBusinessEntity: timestamps, audit, tenant }
+///
+/// 预设展开为同名的 macro_rules!，接收 AutoField 的派生输入，把预设配置作为第一个 #[auto_field(...)]
+/// 属性插入后回调 AutoField，因此实体自身的配置覆盖预设；extends 按宏名解析，遵循普通的宏可见性规则
+#[proc_macro]
+pub fn auto_field_preset(input: TokenStream) -> TokenStream {
+    let preset = parse_macro_input!(input as PresetDefinition);
+    let name = &preset.name;
+    let args = &preset.args;
+    let attr: Attribute = syn::parse_quote! { #[auto_field(#args)] };
+    if let Err(err) = AutoFieldConfig::from_attributes(std::slice::from_ref(&attr)) {
+        return err.to_compile_error().into();
+    }
+    quote! {
+        #[allow(unused_macros)]
+        macro_rules! #name {
+            ([$($callback:tt)*] ($($visited:tt)*) $($input:tt)*) => {
+                $($callback)*! { ($($visited)*) #attr $($input)* }
+            };
+        }
+        #[allow(unused_imports)]
+        pub(crate) use #name;
+    }
+    .into()
+}
+
+/// auto_field_preset! 的内容：`名称: 配置项, ...`
+struct PresetDefinition {
+    name: syn::Ident,
+    args: proc_macro2::TokenStream,
+}
+
+impl syn::parse::Parse for PresetDefinition {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let args = input.parse()?;
+        Ok(Self { name, args })
+    }
+}
+
+/// 为 cfg 条件配置的每种取值组合分别生成实现，并给每个生成项加上对应的 #[cfg(...)]。
+/// 过程宏展开时无法得知使用方 crate 的 cfg，只能把各种组合都展开，交给编译器按 cfg 选择
+fn generate_conditional_impls(
+    input: &DeriveInput,
+    conditional: &[(String, proc_macro2::TokenStream)],
+) -> syn::Result<proc_macro2::TokenStream> {
+    if conditional.len() > MAX_CONDITIONAL_KEYS {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("at most {} auto_field keys can use cfg(...) on one entity", MAX_CONDITIONAL_KEYS)
+        ));
+    }
+
+    let mut output = proc_macro2::TokenStream::new();
+    for mask in 0..(1u32 << conditional.len()) {
+        let mut variant = input.clone();
+        let mut assignments = Vec::new();
+        let mut predicates = Vec::new();
+        for (index, (key, predicate)) in conditional.iter().enumerate() {
+            let key = syn::Ident::new(key, proc_macro2::Span::call_site());
+            let enabled = mask & (1 << index) != 0;
+            assignments.push(quote! { #key = #enabled });
+            predicates.push(if enabled { quote! { #predicate } } else { quote! { not(#predicate) } });
+        }
+        // 追加在原有配置之后，覆盖对应的 cfg 条件
+        variant.attrs.push(syn::parse_quote! { #[auto_field(#(#assignments),*)] });
+
+        let tokens = generate_auto_field_impl(&variant)?;
+        let mut file: syn::File = syn::parse2(tokens)?;
+        for item in &mut file.items {
+            let attrs = item_attrs(item).ok_or_else(|| syn::Error::new_spanned(
+                &input.ident,
+                "cfg(...) configuration produced an item that cannot carry a cfg attribute"
+            ))?;
+            attrs.insert(0, syn::parse_quote! { #[cfg(all(#(#predicates),*))] });
+        }
+        output.extend(quote! { #file });
+    }
+    Ok(output)
+}
+
+/// 生成项的属性列表，用于追加 #[cfg(...)]
+fn item_attrs(item: &mut syn::Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        syn::Item::Const(item) => Some(&mut item.attrs),
+        syn::Item::Enum(item) => Some(&mut item.attrs),
+        syn::Item::Fn(item) => Some(&mut item.attrs),
+        syn::Item::Impl(item) => Some(&mut item.attrs),
+        syn::Item::Macro(item) => Some(&mut item.attrs),
+        syn::Item::Mod(item) => Some(&mut item.attrs),
+        syn::Item::Static(item) => Some(&mut item.attrs),
+        syn::Item::Struct(item) => Some(&mut item.attrs),
+        syn::Item::Trait(item) => Some(&mut item.attrs),
+        syn::Item::Type(item) => Some(&mut item.attrs),
+        syn::Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+/// 生成 AutoField 实现
+fn generate_auto_field_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    // 解析配置
+    let mut config = AutoFieldConfig::from_attributes(&input.attrs)?;
+    if !config.conditional.is_empty() {
+        return generate_conditional_impls(input, &config.conditional);
+    }
+    config.validate()?;
+
+    // skip_all 表示由使用方自行实现，不生成任何代码
+    if config.skip_all {
+        return Ok(quote! {});
+    }
+
+    let struct_name = &input.ident;
+    let fields = match &input.data {
+        syn::Data::Struct(data) => EntityFields::from_fields(&data.fields, struct_name)?,
+        _ => {
+            return Err(syn::Error::new_spanned(struct_name, "AutoField can only be derived for structs"));
+        }
+    };
+    fields.validate_managed(&config, struct_name)?;
+    config.bind_fields(&fields);
+    let active_model_name = config.active_model_type();
+
+    // 生成 ActiveModelBehavior 实现
+    let behavior_impl = generate_active_model_behavior(&config, &fields, struct_name, &active_model_name)?;
+
+    // 生成查询方法共用的基础范围
+    let base_scope_impl = generate_base_scope(&config);
+
+    // 生成 QueryExtensions 实现
+    let query_extensions_impl = generate_query_extensions(&config)?;
+
+    // 生成在指定连接上执行的只读查询
+    let replica_reads_impl = generate_replica_reads(&config, struct_name);
+
+    // 生成批量操作日志的辅助方法
+    let ops_log_impl = generate_ops_log_ext(&config);
+
+    // 生成 CustomizationExt 实现
+    let soft_delete_impl = generate_soft_delete_ext(&config, &fields, &active_model_name)?;
+
+    // 生成回收站查询和恢复方法
+    let recycle_bin_impl = generate_recycle_bin_ext(&config, &fields, struct_name, &active_model_name)?;
+
+    // 生成过期相关查询方法
+    let expiration_impl = generate_expiration_ext(&config)?;
+
+    // 生成软删除数据的保留期执行方法
+    let retention_impl = generate_retention_ext(&config)?;
+
+    // 生成排序相关方法
+    let sort_impl = generate_sort_ext(&config, &fields)?;
+
+    // 生成加密字段的解密方法
+    let decrypt_impl = generate_decrypt_ext(&config, &fields, struct_name)?;
+
+    // 生成行哈希计算函数
+    let hash_impl = generate_hash_ext(&config, &fields, struct_name)?;
+
+    // 生成分片辅助方法
+    let shard_impl = generate_shard_ext(&config, &fields, struct_name)?;
+
+    // 生成脱敏视图
+    let mask_impl = generate_mask_ext(&fields, struct_name)?;
+
+    // 生成统一的创建、更新时间访问方法
+    let timestamp_accessors_impl = generate_timestamp_accessors(&config, struct_name)?;
+
+    // 生成应用更新约定的批量更新方法
+    let update_many_scoped_impl = generate_update_many_scoped(&config)?;
+
+    // 生成雪花 ID 解析方法
+    let snowflake_impl = generate_snowflake_ext(&config)?;
+
+    // 生成批量操作防护
+    let bulk_guard_impl = generate_bulk_guard(&config, &fields)?;
+
+    // 生成租户隔离的关联查询方法
+    let tenant_join_impl = generate_tenant_join_ext(&config, struct_name)?;
+
+    // 生成缓存的雪花 ID 生成器
+    let id_generator_impl = generate_id_generator_cache(&config)?;
+
+    // 生成测试辅助方法
+    let test_support_impl = generate_test_support(&config, &fields, &active_model_name)?;
+
+    // 生成自动填充预演
+    let autofill_plan_impl = generate_autofill_plan(&config, &fields, &active_model_name)?;
+
+    // 生成测试数据工厂
+    let factory_impl = generate_factory_ext(&config, &fields, &active_model_name)?;
+
+    // 生成 ModelBuilder
+    let builder_impl = generate_builder_ext(&config, &fields, struct_name, &active_model_name)?;
+
+    // 生成部分更新载荷
+    let update_payload_impl = generate_update_payload(&config, &fields, struct_name, &active_model_name)?;
+
+    // 生成按表头导入一行的方法
+    let import_impl = generate_import_ext(&config, &fields, &active_model_name)?;
+
+    // 生成导出记录的方法
+    let export_impl = generate_export_ext(&config, &fields, struct_name)?;
+
+    // 生成 sea-query 列值对
+    let sea_query_impl = generate_sea_query_values(&config);
+
+    // 生成 Diesel 变更集
+    let diesel_impl = generate_diesel_changeset(&config, &fields)?;
+
+    // 生成 BSON 文档填充方法
+    let bson_impl = generate_bson_fill(&config, &fields)?;
+
+    // 生成变更事件载荷
+    let change_event_impl = generate_change_event(&config, &fields, struct_name, input)?;
+
+    // 生成 Debezium 格式的变更信封
+    let cdc_envelope_impl = generate_cdc_envelope(&config, &fields, struct_name, input)?;
+
+    // 生成 JSON merge patch 赋值方法
+    let json_patch_impl = generate_json_patch(&config, &fields, &active_model_name)?;
+
+    // 实体注册信息
+    let registry_entry = generate_registry_entry(&config, input)?;
+
+    // 生成 GDPR 匿名化方法
+    let anonymize_impl = generate_anonymize_ext(&config, &fields, struct_name, &active_model_name)?;
+
+    // 个人信息列清单
+    let pii_inventory = generate_pii_inventory(&config, &fields, input)?;
+
+    // 维护任务登记
+    let maintenance_tasks = generate_maintenance_tasks(&config, input)?;
+
+    // 生成受管列元数据
+    let metadata_impl = generate_entity_metadata(&config)?;
+
+    // 输出编译期产物（DDL 片段、索引建议）
+    if config.emit_ddl.is_some() || config.emit_index_hints {
+        let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+            struct_name,
+            "emit_ddl and emit_index_hints require #[sea_orm(table_name = \"...\")] on the entity"
+        ))?;
+        if let Some(dialect) = config.emit_ddl {
+            let ddl = generate_managed_ddl(&config, &table_name, dialect);
+            write_out_dir_artifact(&format!("{}.sql", table_name), &ddl)?;
+        }
+        if config.emit_index_hints {
+            let hints = generate_index_hints(&config, &table_name);
+            write_out_dir_artifact(&format!("{}.indexes.txt", table_name), &hints)?;
+        }
+    }
+
+    // 运行时库别名
+    let runtime_alias = generate_runtime_alias(&config);
+
+    // 输出顺序固定，展开结果只取决于实体定义和配置，便于下游做展开快照
+    Ok(quote! {
+        #runtime_alias
+        #behavior_impl
+        #id_generator_impl
+        #base_scope_impl
+        #query_extensions_impl
+        #replica_reads_impl
+        #ops_log_impl
+        #soft_delete_impl
+        #recycle_bin_impl
+        #expiration_impl
+        #retention_impl
+        #update_many_scoped_impl
+        #snowflake_impl
+        #bulk_guard_impl
+        #tenant_join_impl
+        #sort_impl
+        #decrypt_impl
+        #hash_impl
+        #shard_impl
+        #mask_impl
+        #timestamp_accessors_impl
+        #autofill_plan_impl
+        #factory_impl
+        #builder_impl
+        #update_payload_impl
+        #import_impl
+        #export_impl
+        #sea_query_impl
+        #diesel_impl
+        #bson_impl
+        #change_event_impl
+        #cdc_envelope_impl
+        #anonymize_impl
+        #pii_inventory
+        #test_support_impl
+        #metadata_impl
+        #json_patch_impl
+        #registry_entry
+        #maintenance_tasks
+    })
+}
+
+/// 生成过期相关查询方法
+fn generate_expiration_ext(
+    config: &AutoFieldConfig,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let expire_time_column = config.column("expire_time");
+    if config.ttl_seconds.is_none() {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let now = config.now_expr();
+
+    let find_not_expired_query = config.with_sql_comment("find_not_expired", quote! { Self::find() });
+    let purge_expired_statement = config.sql_comment_statement(
+        "purge_expired",
+        quote! { sea_orm::QueryTrait::build(&Self::auto_field_purge_expired(), backend) },
+    );
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 查询未过期的记录，expire_time 为 NULL 视为永不过期
+            pub fn find_not_expired() -> sea_orm::Select<Self> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                Self::base_scope(
+                    #find_not_expired_query.filter(
+                        sea_orm::Condition::any()
+                            .add(#column_type::#expire_time_column.is_null())
+                            .add(#column_type::#expire_time_column.gt(#now))
+                    )
+                )
+            }
+
+            /// 物理删除所有已过期的记录
+            pub async fn purge_expired<C>(db: &C) -> Result<sea_orm::DeleteResult, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let statement = Self::purge_expired_sql(sea_orm::ConnectionTrait::get_database_backend(db));
+                let result = sea_orm::ConnectionTrait::execute(db, statement).await?;
+                Ok(sea_orm::DeleteResult { rows_affected: result.rows_affected() })
+            }
+
+            /// 返回 purge_expired 将执行的语句，不执行
+            pub fn purge_expired_sql(backend: sea_orm::DbBackend) -> sea_orm::Statement {
+                #purge_expired_statement
+            }
+
+            fn auto_field_purge_expired() -> sea_orm::DeleteMany<Self> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                <Self as sea_orm::EntityTrait>::delete_many()
+                    .filter(#column_type::#expire_time_column.lte(#now))
+            }
+        }
+    })
+}
+
+/// 生成软删除数据的保留期执行方法：软删除超过保留期的记录被物理删除，配置了 archive_to 时先写入历史表。
+/// 删除时间取自软删除时写入、恢复时清空的 delete_time
+fn generate_retention_ext(config: &AutoFieldConfig) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(retention_seconds) = config.retention_seconds else {
+        return Ok(quote! {});
+    };
+
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let delete_time_column = config.column("delete_time");
+    let deleted_condition = config.deleted_condition();
+    let now = config.now_expr();
+    let retention_delete_statement = config.sql_comment_statement(
+        "enforce_retention",
+        quote! { sea_orm::QueryTrait::build(&Self::auto_field_retention_delete(cutoff), backend) },
+    );
+    let retention_archive_statement = config.sql_comment_statement("enforce_retention", quote! { backend.build(&insert) });
+
+    // 历史表的列与 archive_to 的更新前归档一致：实体全部列加 archived_at、archived_by
+    let (archive_statement, archive_exec, archive_sql) = match &config.archive_to {
+        Some(archive_to) => (
+            quote! {
+                fn auto_field_retention_archive(
+                    backend: sea_orm::DbBackend,
+                    cutoff: chrono::NaiveDateTime,
+                ) -> Result<sea_orm::Statement, sea_orm::DbErr> {
+                    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryTrait};
+                    let mut columns: Vec<sea_orm::sea_query::Alias> = <#column_type as sea_orm::Iterable>::iter()
+                        .map(|column| sea_orm::sea_query::Alias::new(sea_orm::IdenStatic::as_str(&column)))
+                        .collect();
+                    columns.push(sea_orm::sea_query::Alias::new("archived_at"));
+                    columns.push(sea_orm::sea_query::Alias::new("archived_by"));
+                    let archived_by = __auto_field_trait::AutoFieldContext::current_safe()
+                        .user_id
+                        .filter(|user_id| !user_id.is_empty());
+                    let mut select = Self::find()
+                        .filter(#deleted_condition)
+                        .filter(#column_type::#delete_time_column.lt(cutoff))
+                        .into_query();
+                    select
+                        .expr(sea_orm::sea_query::Expr::val(#now))
+                        .expr(sea_orm::sea_query::Expr::val(archived_by));
+                    let mut insert = sea_orm::sea_query::Query::insert();
+                    insert
+                        .into_table(sea_orm::sea_query::Alias::new(#archive_to))
+                        .columns(columns)
+                        .select_from(select)
+                        .map_err(|err| sea_orm::DbErr::Custom(err.to_string()))?;
+                    Ok(#retention_archive_statement)
+                }
+            },
+            quote! {
+                let statement = Self::auto_field_retention_archive(sea_orm::ConnectionTrait::get_database_backend(db), cutoff)?;
+                sea_orm::ConnectionTrait::execute(db, statement).await?;
+            },
+            quote! {
+                statements.push(Self::auto_field_retention_archive(backend, cutoff)?);
+            },
+        ),
+        None => (quote! {}, quote! {}, quote! {}),
+    };
+    let log_call = config.ops_log_call(
+        "enforce_retention",
+        quote! { deleted },
+        quote! { format!("{{\"deleted_before\":{}}}", Self::auto_field_json_string(&cutoff.to_string())) },
+    );
+    let archive_doc = match &config.archive_to {
+        Some(archive_to) => format!("删除前先写入历史表 `{}`（archive_to），", archive_to),
+        None => String::new(),
+    };
+    let enforce_doc = doc_attrs(&format!(
+        "物理删除软删除超过 {} 秒的记录，{}返回删除的条数，供定时任务调用\n\n删除时间取自软删除时写入的 `delete_time`；需要归档和删除原子执行时在事务中调用",
+        retention_seconds, archive_doc
+    ));
+
+    Ok(quote! {
+        impl #entity_name {
+            #enforce_doc
+            pub async fn enforce_retention<C>(db: &C) -> Result<u64, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let cutoff = Self::auto_field_retention_cutoff();
+                #archive_exec
+                let statement = Self::auto_field_retention_delete_statement(sea_orm::ConnectionTrait::get_database_backend(db), cutoff);
+                let deleted = sea_orm::ConnectionTrait::execute(db, statement).await?.rows_affected();
+                #log_call
+                Ok(deleted)
+            }
+
+            /// 返回 enforce_retention 将执行的语句，不执行
+            pub fn enforce_retention_sql(backend: sea_orm::DbBackend) -> Result<Vec<sea_orm::Statement>, sea_orm::DbErr> {
+                let cutoff = Self::auto_field_retention_cutoff();
+                #[allow(unused_mut)]
+                let mut statements = Vec::new();
+                #archive_sql
+                statements.push(Self::auto_field_retention_delete_statement(backend, cutoff));
+                Ok(statements)
+            }
+
+            fn auto_field_retention_cutoff() -> chrono::NaiveDateTime {
+                #now - chrono::Duration::seconds(#retention_seconds)
+            }
+
+            fn auto_field_retention_delete_statement(backend: sea_orm::DbBackend, cutoff: chrono::NaiveDateTime) -> sea_orm::Statement {
+                #retention_delete_statement
+            }
+
+            fn auto_field_retention_delete(cutoff: chrono::NaiveDateTime) -> sea_orm::DeleteMany<Self> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                <Self as sea_orm::EntityTrait>::delete_many()
+                    .filter(#deleted_condition)
+                    .filter(#column_type::#delete_time_column.lt(cutoff))
+            }
+
+            #archive_statement
+        }
+    })
+}
+
+/// 生成雪花 ID 解析方法。ID 高位是相对纪元的毫秒数（低 22 位为机器、节点和序号），
+/// 因此时间范围可以换算为 ID 范围，按主键索引扫描
+fn generate_snowflake_ext(
+    config: &AutoFieldConfig,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let id_column = config.column("id");
+    if !config.snowflake_id {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let epoch_ms = config.snowflake_epoch_ms.unwrap_or(0);
+
+    let find_created_between_ids_query = config.with_sql_comment("find_created_between_ids", quote! { Self::find() });
+    // id_worker_from = "tenant" 时 ID 末尾两位是租户分片号，解析前去掉，下界补 00
+    let (strip_shard, shard_floor) = if config.id_worker_from == IdWorkerSource::Tenant {
+        (quote! { let id = id.get(..id.len().checked_sub(2)?)?; }, "00")
+    } else {
+        (quote! {}, "")
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 从雪花 ID 解析生成时间（毫秒精度），ID 不是数字时返回 None
+            pub fn id_timestamp(id: &str) -> Option<chrono::NaiveDateTime> {
+                #strip_shard
+                let id: i64 = id.parse().ok()?;
+                chrono::DateTime::from_timestamp_millis((id >> 22) + #epoch_ms).map(|time| time.naive_utc())
+            }
+
+            /// 给定时间点生成的最小雪花 ID
+            pub fn id_lower_bound(time: chrono::NaiveDateTime) -> String {
+                let millis = (time.and_utc().timestamp_millis() - #epoch_ms).max(0);
+                format!("{}{}", millis << 22, #shard_floor)
+            }
+
+            /// 查询 [start, end) 时间段内创建的记录，条件落在主键上。
+            /// id 为字符串列，上下界长度相同（当前的雪花 ID 总是如此）时按主键范围扫描，长度只作为剩余过滤条件；
+            /// 长度不同时先比较长度再按字典序比较，与按数值比较一致，但无法使用主键索引
+            pub fn find_created_between_ids(
+                start: chrono::NaiveDateTime,
+                end: chrono::NaiveDateTime,
+            ) -> sea_orm::Select<Self> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                use sea_orm::sea_query::{Expr, Func};
+                let id_length = || Expr::expr(Func::char_length(Expr::col((Self, #column_type::#id_column))));
+                let start_id = Self::id_lower_bound(start);
+                let end_id = Self::id_lower_bound(end);
+                let (start_length, end_length) = (start_id.len() as i32, end_id.len() as i32);
+                let condition = if start_length == end_length {
+                    sea_orm::Condition::all()
+                        .add(#column_type::#id_column.gte(start_id))
+                        .add(#column_type::#id_column.lt(end_id))
+                        .add(id_length().eq(start_length))
+                } else {
+                    sea_orm::Condition::all()
+                        .add(
+                            sea_orm::Condition::any()
+                                .add(id_length().gt(start_length))
+                                .add(sea_orm::Condition::all().add(id_length().eq(start_length)).add(#column_type::#id_column.gte(start_id)))
+                        )
+                        .add(
+                            sea_orm::Condition::any()
+                                .add(id_length().lt(end_length))
+                                .add(sea_orm::Condition::all().add(id_length().eq(end_length)).add(#column_type::#id_column.lt(end_id)))
+                        )
+                };
+                Self::base_scope(#find_created_between_ids_query.filter(condition))
+            }
+        }
+    })
+}
+
+/// 生成应用更新约定的批量更新方法，基于 CustomizationExt::batch_update
+fn generate_update_many_scoped(
+    config: &AutoFieldConfig,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let entity_name = config.entity_type();
+    let managed_column_assigned = auto_field_error(quote! {
+        ManagedColumnAssigned { column: column_name.to_string() }
+    });
+    let operator_check = if config.audit_required {
+        let current_context = config.current_context();
+        let operator_present = operator_present();
+        let operator_required = auto_field_error(quote! {
+            OperatorRequired { table: sea_orm::EntityName::table_name(&Self::default()).to_string() }
+        });
+        quote! {
+            #current_context
+            if !#operator_present {
+                return Err(#operator_required);
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // 配置了 ops_log 时记录完整的 UPDATE 语句（参数已内联）
+    let exec = if config.ops_log.is_some() {
+        let log_call = config.ops_log_call(
+            "update_many_scoped",
+            quote! { result.rows_affected },
+            quote! { format!("{{\"statement\":{}}}", Self::auto_field_json_string(&statement)) },
+        );
+        quote! {
+            let statement = sea_orm::QueryTrait::build(&update_many, sea_orm::ConnectionTrait::get_database_backend(db)).to_string();
+            let result = update_many.exec(db).await?;
+            #log_call
+            Ok(result)
+        }
+    } else {
+        quote! { update_many.exec(db).await }
+    };
+
+    // 由数据库维护时间戳时，只有 MySQL 的 ON UPDATE CURRENT_TIMESTAMP 会刷新 update_time，
+    // PostgreSQL 和 SQLite 需要在语句中写入当前时间
+    let database_update_time = if config.timestamps && !config.fills_timestamps() {
+        let update_time = config.column("update_time");
+        quote! {
+            match backend {
+                sea_orm::DbBackend::MySql => update_many,
+                sea_orm::DbBackend::Postgres => update_many.col_expr(
+                    #column_type::#update_time,
+                    sea_orm::sea_query::Expr::cust("LOCALTIMESTAMP")
+                ),
+                sea_orm::DbBackend::Sqlite => update_many.col_expr(
+                    #column_type::#update_time,
+                    sea_orm::sea_query::Expr::cust("strftime('%Y-%m-%d %H:%M:%f', 'now')")
+                ),
+            }
+        }
+    } else {
+        quote! {
+            let _ = backend;
+            update_many
+        }
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 按数据库后端生成的 batch_update：由数据库维护时间戳时，为没有 ON UPDATE 的后端写入 update_time，
+            /// 其余更新约定与 batch_update 相同
+            pub fn batch_update_for(backend: sea_orm::DbBackend) -> sea_orm::UpdateMany<Self> {
+                let update_many = <Self as __auto_field_trait::CustomizationExt>::batch_update();
+                #database_update_time
+            }
+
+            /// 批量更新满足条件的记录，自动追加 update_time、更新人和 version + 1，
+            /// set_columns 中不能包含受管列
+            pub async fn update_many_scoped<C>(
+                db: &C,
+                condition: sea_orm::Condition,
+                set_columns: Vec<(#column_type, sea_orm::sea_query::SimpleExpr)>,
+            ) -> Result<sea_orm::UpdateResult, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let update_many = Self::auto_field_update_many_scoped(
+                    sea_orm::ConnectionTrait::get_database_backend(db),
+                    condition,
+                    set_columns,
+                )?;
+                #exec
+            }
+
+            /// 返回 update_many_scoped 将执行的语句，不执行；校验与 update_many_scoped 相同
+            pub fn update_many_scoped_sql(
+                backend: sea_orm::DbBackend,
+                condition: sea_orm::Condition,
+                set_columns: Vec<(#column_type, sea_orm::sea_query::SimpleExpr)>,
+            ) -> Result<sea_orm::Statement, sea_orm::DbErr> {
+                let update_many = Self::auto_field_update_many_scoped(backend, condition, set_columns)?;
+                Ok(sea_orm::QueryTrait::build(&update_many, backend))
+            }
+
+            fn auto_field_update_many_scoped(
+                backend: sea_orm::DbBackend,
+                condition: sea_orm::Condition,
+                set_columns: Vec<(#column_type, sea_orm::sea_query::SimpleExpr)>,
+            ) -> Result<sea_orm::UpdateMany<Self>, sea_orm::DbErr> {
+                use sea_orm::QueryFilter;
+                #operator_check
+                let mut update_many = Self::batch_update_for(backend);
+                for (column, value) in set_columns {
+                    let column_name = sea_orm::IdenStatic::as_str(&column);
+                    if Self::AUTO_FIELD_MANAGED.contains(&column_name) {
+                        return Err(#managed_column_assigned);
+                    }
+                    update_many = update_many.col_expr(column, value);
+                }
+                Ok(update_many.filter(condition))
+            }
+        }
+    })
+}
+
+/// 生成与 EntityTrait::update_many / delete_many 同名的固有方法，防止批量操作绕过约定。
+/// 固有方法优先于 trait 方法，Entity::update_many() 会解析到这里，需要原始行为时使用
+/// <Entity as EntityTrait>::update_many()
+fn generate_bulk_guard(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let deleted = config.deleted_value();
+    let tenant_id_column = config.column("tenant_id");
+    let delete_flag_column = config.column("delete_flag");
+    let entity_name = config.entity_type();
+    // 只有存在更新约定或软删除时才需要防护
+    let guard_update = config.fills_timestamps() || config.audit || config.version;
+    let guard_delete = config.soft_delete;
+
+    let methods = match config.bulk_guard {
+        BulkGuard::Off => return Ok(quote! {}),
+        BulkGuard::Deprecate => {
+            let mut methods = Vec::new();
+            if guard_update {
+                methods.push(quote! {
+                    #[deprecated(note = "update_many bypasses auto_field update conventions, use update_many_scoped or batch_update")]
+                    pub fn update_many() -> sea_orm::UpdateMany<Self> {
+                        <Self as sea_orm::EntityTrait>::update_many()
+                    }
+                });
+            }
+            if guard_delete {
+                methods.push(quote! {
+                    #[deprecated(note = "delete_many bypasses soft delete, use soft_delete_many")]
+                    pub fn delete_many() -> sea_orm::DeleteMany<Self> {
+                        <Self as sea_orm::EntityTrait>::delete_many()
+                    }
+                });
+            }
+            methods
+        }
+        BulkGuard::Wrap => {
+            // 上下文中有租户时，批量操作只作用于当前租户
+            let tenant_filter = if config.tenant {
+                let context_capture = config.context_capture();
+                quote! {
+                    use sea_orm::ColumnTrait;
+                    #context_capture
+                    if let Some(tenant_id) = context.tenant_id.as_ref().filter(|tenant_id| !tenant_id.is_empty()) {
+                        query = sea_orm::QueryFilter::filter(query, #column_type::#tenant_id_column.eq(tenant_id.clone()));
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            let mut methods = Vec::new();
+            if guard_update || config.tenant {
+                methods.push(quote! {
+                    /// 批量更新，自动追加更新约定和租户条件
+                    pub fn update_many() -> sea_orm::UpdateMany<Self> {
+                        #[allow(unused_mut)]
+                        let mut query = <Self as __auto_field_trait::CustomizationExt>::batch_update();
+                        #tenant_filter
+                        query
+                    }
+                });
+            }
+            if guard_delete {
+                let delete_state = delete_state_values(config, fields, false)?
+                    .into_iter()
+                    .map(|(_, variant, value)| quote! { .col_expr(#column_type::#variant, sea_orm::sea_query::Expr::value(#value)) });
+                methods.push(quote! {
+                    /// 批量删除转为批量软删除，返回 UpdateMany，filter / exec 的用法不变
+                    pub fn delete_many() -> sea_orm::UpdateMany<Self> {
+                        #[allow(unused_mut)]
+                        let mut query = <Self as __auto_field_trait::CustomizationExt>::batch_update()
+                            .col_expr(#column_type::#delete_flag_column, sea_orm::sea_query::Expr::value(#deleted))
+                            #(#delete_state)*;
+                        #tenant_filter
+                        query
+                    }
+                });
+            } else if config.tenant {
+                methods.push(quote! {
+                    /// 批量删除，自动追加租户条件
+                    pub fn delete_many() -> sea_orm::DeleteMany<Self> {
+                        #[allow(unused_mut)]
+                        let mut query = <Self as sea_orm::EntityTrait>::delete_many();
+                        #tenant_filter
+                        query
+                    }
+                });
+            }
+            methods
+        }
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            #(#methods)*
+        }
+    })
+}
+
+/// 生成按租户隔离的关联查询方法，避免关联查询跨租户取数
+fn generate_tenant_join_ext(
+    config: &AutoFieldConfig,
+    struct_name: &syn::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let tenant_id = config.field("tenant_id");
+    if !config.tenant {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let tenant_id_column = config.column("tenant_id");
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 在关联条件上追加本实体租户列与 related_tenant 相等，relation 须从本实体出发，
+            /// related_tenant 为关联实体的租户列
+            pub fn tenant_scoped<C>(relation: sea_orm::RelationDef, related_tenant: C) -> sea_orm::RelationDef
+            where
+                C: sea_orm::ColumnTrait,
+            {
+                relation.on_condition(move |left, right| {
+                    sea_orm::Condition::all().add(
+                        sea_orm::sea_query::Expr::col((left, #column_type::#tenant_id_column))
+                            .equals((right, related_tenant))
+                    )
+                })
+            }
+
+            /// 按租户隔离关联，join 时使用 tenant_scoped 的关联定义
+            pub fn join_tenant_scoped<C>(
+                select: sea_orm::Select<Self>,
+                join_type: sea_orm::JoinType,
+                relation: sea_orm::RelationDef,
+                related_tenant: C,
+            ) -> sea_orm::Select<Self>
+            where
+                C: sea_orm::ColumnTrait,
+            {
+                use sea_orm::QuerySelect;
+                select.join(join_type, Self::tenant_scoped(relation, related_tenant))
+            }
+        }
+
+        impl #struct_name {
+            /// 查询与当前记录同租户的关联记录，related_tenant 为关联实体的租户列，
+            /// 当前记录没有租户时不返回任何记录
+            pub fn find_related_in_tenant<R>(&self, related: R, related_tenant: R::Column) -> sea_orm::Select<R>
+            where
+                R: sea_orm::EntityTrait,
+                #entity_name: sea_orm::Related<R>,
+            {
+                use sea_orm::{ColumnTrait, ModelTrait, QueryFilter};
+                let tenant_filter = match &self.#tenant_id {
+                    Some(tenant_id) => related_tenant.eq(tenant_id.clone()),
+                    None => sea_orm::sea_query::Expr::value(false),
+                };
+                self.find_related(related).filter(tenant_filter)
+            }
+        }
+    })
+}
+
+/// 查找排序列对应的字段，返回 (字段, 是否为 Option)
+fn sort_field<'a>(config: &AutoFieldConfig, fields: &'a EntityFields) -> syn::Result<Option<(&'a EntityField, bool)>> {
+    let Some(column) = &config.sort_column else {
+        return Ok(None);
+    };
+    let field = fields.get(column).ok_or_else(|| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("sort is enabled but the struct has no `{}` field", column)
+    ))?;
+    Ok(Some((field, option_inner_type(&field.ty).is_some())))
+}
+
+/// 生成排序列的插入填充逻辑，target 为 self 或 active_model
+fn generate_sort_fill(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+    with_db: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let Some((field, is_option)) = sort_field(config, fields)? else {
+        return Ok(quote! {});
+    };
+    let ident = &field.ident;
+    let ty = &field.ty;
+    let value_ty = option_inner_type(ty).unwrap_or(ty);
+    let variant = column_variant(&field.name, ident.span());
+
+    let value = match config.sort_mode {
+        SortMode::Zero => quote! { 0 },
+        SortMode::Append if with_db => quote! {
+            {
+                use sea_orm::{ColumnTrait, EntityTrait, QuerySelect};
+                let max_sort = #entity_name::find()
+                    .select_only()
+                    .column_as(#column_type::#variant.max(), "max_sort")
+                    .into_tuple::<Option<#value_ty>>()
+                    .one(db)
+                    .await?
+                    .flatten();
+                max_sort.map(|max_sort| max_sort + 1).unwrap_or(0)
+            }
+        },
+        // 批量插入没有数据库连接，追加模式下保留列默认值
+        SortMode::Append => return Ok(quote! {}),
+    };
+
+    Ok(if is_option {
+        quote! {
+            if should_fill_field!(#target.#ident) {
+                #target.#ident = sea_orm::ActiveValue::Set(Some(#value));
+            }
+        }
+    } else {
+        quote! {
+            if should_fill_field!(#target.#ident, #ty) {
+                #target.#ident = sea_orm::ActiveValue::Set(#value);
+            }
+        }
+    })
+}
+
+/// 生成读取 ActiveValue 中字符串值的表达式，结果为 Option<&str>；only_set 时只读取本次修改（Set）的值
+fn active_str_value(field: &EntityField, target: &proc_macro2::TokenStream, only_set: bool) -> proc_macro2::TokenStream {
+    let ident = &field.ident;
+    let value = if option_inner_type(&field.ty).is_some() {
+        quote! { value.as_deref() }
+    } else {
+        quote! { Some(value.as_str()) }
+    };
+    if only_set {
+        quote! {
+            match &#target.#ident {
+                sea_orm::ActiveValue::Set(value) => #value,
+                _ => None,
+            }
+        }
+    } else {
+        quote! {
+            match &#target.#ident {
+                sea_orm::ActiveValue::Set(value) | sea_orm::ActiveValue::Unchanged(value) => #value,
+                sea_orm::ActiveValue::NotSet => None,
+            }
+        }
+    }
+}
+
+/// 生成为字段赋值的 ActiveValue::Set 表达式，Option 字段自动包装 Some
+fn active_set_value(field: &EntityField, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if option_inner_type(&field.ty).is_some() {
+        quote! { sea_orm::ActiveValue::Set(Some(#value)) }
+    } else {
+        quote! { sea_orm::ActiveValue::Set(#value) }
+    }
+}
+
+/// 校验加密字段配置，返回所有加密字段
+fn encrypted_fields<'a>(config: &AutoFieldConfig, fields: &'a EntityFields) -> syn::Result<Vec<&'a EntityField>> {
+    let encrypted: Vec<&EntityField> = fields.fields.iter().filter(|field| field.config.encrypt).collect();
+    if let Some(field) = encrypted.first() {
+        if config.encrypt_fn.is_none() || config.decrypt_fn.is_none() {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "encrypt requires encrypt_fn and decrypt_fn in the struct-level auto_field attribute"
+            ));
+        }
+    }
+    Ok(encrypted)
+}
+
+/// 生成加密字段的保存逻辑，字段被修改（Set）时加密；fallible 为 false 时（批量插入）加密失败直接 panic
+fn generate_encrypt_fills(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+    fallible: bool,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut fills = Vec::new();
+    for field in encrypted_fields(config, fields)? {
+        let encrypt_fn = &config.encrypt_fn;
+        let ident = &field.ident;
+        let source = active_str_value(field, target, true);
+        let column = &field.name;
+        let message = config.message(Message::EncryptFailed);
+        let error = auto_field_error(quote! {
+            EncryptFailed { column: #column.to_string(), message: err.to_string() }
+        });
+        let encrypted = if fallible {
+            quote! {
+                #encrypt_fn(plain).map_err(|err| #error)?
+            }
+        } else {
+            quote! {
+                match #encrypt_fn(plain) {
+                    Ok(encrypted) => encrypted,
+                    Err(err) => panic!(#message, #column, err),
+                }
+            }
+        };
+        let value = active_set_value(field, quote! { encrypted.into() });
+        fills.push(quote! {
+            if let Some(plain) = #source {
+                let encrypted: String = #encrypted;
+                #target.#ident = #value;
+            }
+        });
+    }
+    Ok(fills)
+}
+
+/// 生成 Model::decrypt，返回解密后的副本
+fn generate_decrypt_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let encrypted = encrypted_fields(config, fields)?;
+    if encrypted.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let decrypt_fn = &config.decrypt_fn;
+    let decrypts = encrypted.iter().map(|field| {
+        let ident = &field.ident;
+        let column = &field.name;
+        let error = auto_field_error(quote! {
+            DecryptFailed { column: #column.to_string(), message: err.to_string() }
+        });
+        let decrypt = quote! {
+            |cipher: &str| #decrypt_fn(cipher)
+                .map(Into::into)
+                .map_err(|err| #error)
+        };
+        if option_inner_type(&field.ty).is_some() {
+            quote! { model.#ident = model.#ident.as_deref().map(#decrypt).transpose()?; }
+        } else {
+            quote! { model.#ident = (#decrypt)(model.#ident.as_str())?; }
+        }
+    });
+
+    Ok(quote! {
+        impl #struct_name {
+            /// 返回加密字段解密后的副本
+            pub fn decrypt(&self) -> Result<Self, sea_orm::DbErr> {
+                let mut model = self.clone();
+                #(#decrypts)*
+                Ok(model)
+            }
+        }
+    })
+}
+
+/// 查找行哈希的来源字段与目标字段
+fn hash_fields<'a>(
+    config: &AutoFieldConfig,
+    fields: &'a EntityFields,
+) -> syn::Result<Option<(Vec<&'a EntityField>, &'a EntityField)>> {
+    let Some(hash) = &config.hash else {
+        return Ok(None);
+    };
+    let missing = |column: &str| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("hash is enabled but the struct has no `{}` field", column)
+    );
+    let from = hash.from
+        .iter()
+        .map(|column| fields.get(column).ok_or_else(|| missing(column)))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let into = fields.get(&hash.into).ok_or_else(|| missing(&hash.into))?;
+    Ok(Some((from, into)))
+}
+
+/// 生成将字段值转换为哈希输入的表达式，NULL 视为空字符串
+fn hash_part(field: &EntityField, value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if option_inner_type(&field.ty).is_some() {
+        quote! { #value.as_ref().map(ToString::to_string).unwrap_or_default() }
+    } else {
+        quote! { #value.to_string() }
+    }
+}
+
+/// 生成行哈希列的维护逻辑，插入时总是计算，更新时仅在来源列被修改时计算，target 为 self 或 active_model
+fn generate_hash_fill(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+    insert: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some((from, into)) = hash_fields(config, fields)? else {
+        return Ok(quote! {});
+    };
+
+    let parts = from.iter().map(|field| {
+        let ident = &field.ident;
+        let part = hash_part(field, quote! { value });
+        quote! {
+            match &#target.#ident {
+                sea_orm::ActiveValue::Set(value) | sea_orm::ActiveValue::Unchanged(value) => Some(#part),
+                sea_orm::ActiveValue::NotSet => None,
+            }
+        }
+    });
+    let changed = if insert {
+        quote! { true }
+    } else {
+        let idents = from.iter().map(|field| &field.ident);
+        quote! { #(matches!(#target.#idents, sea_orm::ActiveValue::Set(_)))||* }
+    };
+    let into_ident = &into.ident;
+    let value = active_set_value(into, quote! { Model::auto_field_row_hash(&parts).into() });
+
+    // 部分字段未加载（NotSet）时无法得到完整的行哈希，保持原值
+    Ok(quote! {
+        if #changed {
+            let parts: Option<Vec<String>> = [#(#parts),*].into_iter().collect();
+            if let Some(parts) = parts {
+                #target.#into_ident = #value;
+            }
+        }
+    })
+}
+
+/// 生成行哈希计算函数
+fn generate_hash_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some((from, into)) = hash_fields(config, fields)? else {
+        return Ok(quote! {});
+    };
+    let entity_name = config.entity_type();
+
+    let parts = from.iter().map(|field| {
+        let ident = &field.ident;
+        hash_part(field, quote! { self.#ident })
+    });
+    let into_ident = &into.ident;
+    // 行哈希列为 NULL 视为未通过校验
+    let stored = if option_inner_type(&into.ty).is_some() {
+        quote! { self.#into_ident.as_ref().map(|value| ::std::convert::AsRef::<str>::as_ref(value)) }
+    } else {
+        quote! { Some(::std::convert::AsRef::<str>::as_ref(&self.#into_ident)) }
+    };
+
+    let find_tampered_query = config.with_sql_comment("find_tampered", quote! { Self::find() });
+
+    Ok(quote! {
+        impl #struct_name {
+            /// 按来源列重新计算行哈希并与行哈希列比较，不一致说明记录被绕过应用修改过
+            pub fn verify_integrity(&self) -> bool {
+                let parts = [#(#parts),*];
+                #stored == Some(Self::auto_field_row_hash(&parts).as_str())
+            }
+
+            /// 计算行哈希：各列文本值以 0x1F 分隔后做 SHA-256，输出小写十六进制
+            #[doc(hidden)]
+            pub fn auto_field_row_hash(parts: &[String]) -> String {
+                use ::sha2::{Digest, Sha256};
+                let mut hasher = Sha256::new();
+                for (index, part) in parts.iter().enumerate() {
+                    if index > 0 {
+                        hasher.update([0x1f]);
+                    }
+                    hasher.update(part.as_bytes());
+                }
+                hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+            }
+        }
+
+        impl #entity_name {
+            /// 全表扫描（包括已软删除的记录），按主键顺序分批读取，返回行哈希校验失败的记录
+            pub async fn find_tampered<C>(db: &C) -> Result<Vec<#struct_name>, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::{EntityTrait, Iterable, PaginatorTrait, PrimaryKeyToColumn, QueryOrder};
+                let mut select = #find_tampered_query;
+                for key in <Self as EntityTrait>::PrimaryKey::iter() {
+                    select = select.order_by_asc(key.into_column());
+                }
+                let mut pages = select.paginate(db, 500);
+                let mut tampered = Vec::new();
+                while let Some(models) = pages.fetch_and_next().await? {
+                    tampered.extend(models.into_iter().filter(|model| !model.verify_integrity()));
+                }
+                Ok(tampered)
+            }
+        }
+    })
+}
+
+/// 生成分片辅助方法，分片号由 shard_fn 根据分片键的文本值计算
+fn generate_shard_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let (Some(shard_key), Some(shard_fn)) = (&config.shard_key, &config.shard_fn) else {
+        return Ok(quote! {});
+    };
+    let field = fields.get(shard_key).ok_or_else(|| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("shard_key is enabled but the struct has no `{}` field", shard_key)
+    ))?;
+    let inner_ty = option_inner_type(&field.ty).unwrap_or(&field.ty);
+    if !is_string_like_type(inner_ty) {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "shard_key must be a String or Option<String> field"
+        ));
+    }
+
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let ident = &field.ident;
+    let variant = column_variant(&field.name, ident.span());
+    // 分片键为 NULL 时按空字符串计算
+    let key = if option_inner_type(&field.ty).is_some() {
+        quote! { model.#ident.as_ref().map(|value| ::std::convert::AsRef::<str>::as_ref(value)).unwrap_or_default() }
+    } else {
+        quote! { ::std::convert::AsRef::<str>::as_ref(&model.#ident) }
+    };
+    let find_on_shard_query = config.with_sql_comment("find_on_shard", quote! { Self::find() });
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 计算分片键对应的分片号
+            pub fn shard_of_key(key: &str) -> u32 {
+                #shard_fn(key)
+            }
+
+            /// 计算记录所在的分片号
+            pub fn shard_of(model: &#struct_name) -> u32 {
+                Self::shard_of_key(#key)
+            }
+
+            /// 查询分片键属于指定分片的记录，不属于该分片的键被忽略；启用软删除时排除已删除的记录
+            pub fn find_on_shard<'a, I>(shard: u32, keys: I) -> sea_orm::Select<Self>
+            where
+                I: IntoIterator<Item = &'a str>,
+            {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                let keys: Vec<&str> = keys.into_iter().filter(|key| Self::shard_of_key(key) == shard).collect();
+                Self::base_scope(#find_on_shard_query.filter(#column_type::#variant.is_in(keys)))
+            }
+        }
+    })
+}
+
+/// 生成 Model::created_at / updated_at，统一以 DateTime<Utc> 返回创建、更新时间。
+/// 时间戳列按 UTC 的 NaiveDateTime 存储（now 取自 naive_utc），这里补上时区
+fn generate_timestamp_accessors(config: &AutoFieldConfig, struct_name: &syn::Ident) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.timestamps {
+        return Ok(quote! {});
+    }
+
+    let create_time = config.field("create_time");
+    let update_time = config.field("update_time");
+    let to_utc = config.timestamp_to_utc(quote! { time });
+    Ok(quote! {
+        impl #struct_name {
+            /// 创建时间（UTC），与列的存储类型无关
+            pub fn created_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+                self.#create_time.and_then(|time| #to_utc)
+            }
+
+            /// 最后更新时间（UTC），与列的存储类型无关
+            pub fn updated_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+                self.#update_time.and_then(|time| #to_utc)
+            }
+        }
+    })
+}
+
+/// 生成 Model::masked，返回脱敏后的副本
+fn generate_mask_ext(fields: &EntityFields, struct_name: &syn::Ident) -> syn::Result<proc_macro2::TokenStream> {
+    let mut masks = Vec::new();
+    for field in &fields.fields {
+        let Some(kind) = field.config.mask else {
+            continue;
+        };
+        let ident = &field.ident;
+        let is_option = option_inner_type(&field.ty).is_some();
+        if !is_string_like_type(option_inner_type(&field.ty).unwrap_or(&field.ty)) {
+            return Err(syn::Error::new_spanned(&field.ty, "mask requires a String-like field (String, SmolStr, Cow<str> or an Option of one)"));
+        }
+
+        let (head, tail) = kind.keep();
+        let mask = if kind == MaskKind::Email {
+            quote! {
+                |value: &str| match value.split_once('@') {
+                    Some((local, domain)) => format!("{}@{}", mask_keep(local, #head, #tail), domain),
+                    None => mask_keep(value, #head, #tail),
+                }
+            }
+        } else {
+            quote! { |value: &str| mask_keep(value, #head, #tail) }
+        };
+        // 字符串类列通过 AsRef<str> 读取，脱敏结果经 From<String> 转回列类型
+        masks.push(if is_option {
+            quote! {
+                model.#ident = model.#ident.as_ref().map(|value| (#mask)(::std::convert::AsRef::<str>::as_ref(value)).into());
+            }
+        } else {
+            quote! { model.#ident = (#mask)(::std::convert::AsRef::<str>::as_ref(&model.#ident)).into(); }
+        });
+    }
+
+    if masks.is_empty() {
+        return Ok(quote! {});
+    }
+
+    Ok(quote! {
+        impl #struct_name {
+            /// 返回脱敏后的副本，用于日志和低权限接口响应
+            pub fn masked(&self) -> Self {
+                // 保留首尾字符，中间替换为 *；长度不足时全部替换
+                fn mask_keep(value: &str, head: usize, tail: usize) -> String {
+                    let chars: Vec<char> = value.chars().collect();
+                    if chars.len() <= head + tail {
+                        return "*".repeat(chars.len());
+                    }
+                    let mut masked: String = chars[..head].iter().collect();
+                    masked.push_str(&"*".repeat(chars.len() - head - tail));
+                    masked.extend(&chars[chars.len() - tail..]);
+                    masked
+                }
+
+                let mut model = self.clone();
+                #(#masks)*
+                model
+            }
+        }
+    })
+}
+
+/// 生成派生列同步逻辑：来源列本次被赋值（Set）时，用映射函数重新计算目标列
+fn generate_sync_fills(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let missing = |column: &str| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("sync is enabled but the struct has no `{}` field", column)
+    );
+
+    let mut fills = Vec::new();
+    for rule in &config.sync {
+        let from_ident = &fields.get(&rule.from).ok_or_else(|| missing(&rule.from))?.ident;
+        let into_ident = &fields.get(&rule.into).ok_or_else(|| missing(&rule.into))?.ident;
+        let map = &rule.map;
+        fills.push(quote! {
+            if let sea_orm::ActiveValue::Set(source) = &#target.#from_ident {
+                #target.#into_ident = sea_orm::ActiveValue::Set(#map(source));
+            }
+        });
+    }
+    Ok(fills)
+}
+
+/// 生成拼音检索列的填充逻辑，来源列被修改（Set）时重新计算，target 为 self 或 active_model
+fn generate_pinyin_fills(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let missing = |column: &str| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("pinyin is enabled but the struct has no `{}` field", column)
+    );
+
+    let mut fills = Vec::new();
+    for rule in &config.pinyin {
+        let from_field = fields.get(&rule.from).ok_or_else(|| missing(&rule.from))?;
+        let source = active_str_value(from_field, target, true);
+
+        let mut assignments = Vec::new();
+        if let Some(full) = &rule.full {
+            let field = fields.get(full).ok_or_else(|| missing(full))?;
+            let ident = &field.ident;
+            let value = active_set_value(field, quote! { full.into() });
+            assignments.push(quote! { #target.#ident = #value; });
+        }
+        if let Some(initials) = &rule.initials {
+            let field = fields.get(initials).ok_or_else(|| missing(initials))?;
+            let ident = &field.ident;
+            let value = active_set_value(field, quote! { initials.into() });
+            assignments.push(quote! { #target.#ident = #value; });
+        }
+
+        fills.push(quote! {
+            if let Some(source) = #source {
+                use ::pinyin::ToPinyin;
+                // 非汉字字符按小写原样保留
+                let mut full = String::with_capacity(source.len() * 2);
+                let mut initials = String::with_capacity(source.len());
+                for (ch, pinyin) in source.chars().zip(source.to_pinyin()) {
+                    match pinyin {
+                        Some(pinyin) => {
+                            full.push_str(pinyin.plain());
+                            initials.push_str(pinyin.first_letter());
+                        }
+                        None => {
+                            full.extend(ch.to_lowercase());
+                            initials.extend(ch.to_lowercase());
+                        }
+                    }
+                }
+                #(#assignments)*
+            }
+        });
+    }
+    Ok(fills)
+}
+
+/// 生成 slug 的插入填充逻辑，target 为 self 或 active_model
+fn generate_slug_fill(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+    with_db: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let Some(slug) = &config.slug else {
+        return Ok(quote! {});
+    };
+    let missing = |column: &str| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("slug is enabled but the struct has no `{}` field", column)
+    );
+    let from_field = fields.get(&slug.from).ok_or_else(|| missing(&slug.from))?;
+    let into_field = fields.get(&slug.into).ok_or_else(|| missing(&slug.into))?;
+    let into_ident = &into_field.ident;
+    let into_ty = &into_field.ty;
+    let into_variant = column_variant(&slug.into, into_ident.span());
+    let source = active_str_value(from_field, target, false);
+
+    let transliterate = match &slug.transliterate {
+        Some(transliterate) => quote! { #transliterate(source) },
+        None => quote! { source.to_string() },
+    };
+    // 插入时一次查出同名及带数字后缀的 slug，取最大后缀 + 1；启用 tenant 时只在同租户内去重。
+    // 批量插入没有数据库连接，不做去重，唯一性由数据库唯一索引保证
+    let dedupe = if with_db {
+        let tenant_filter = if config.tenant {
+            let tenant_id = config.field("tenant_id");
+            let tenant_id_column = config.column("tenant_id");
+            quote! {
+                let query = match &#target.#tenant_id {
+                    sea_orm::ActiveValue::Set(Some(tenant_id)) | sea_orm::ActiveValue::Unchanged(Some(tenant_id)) => {
+                        query.filter(#column_type::#tenant_id_column.eq(tenant_id.clone()))
+                    }
+                    _ => query.filter(#column_type::#tenant_id_column.is_null()),
+                };
+            }
+        } else {
+            quote! {}
+        };
+        quote! {
+            use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+            // base 只含字母数字和 -，不会出现 LIKE 通配符
+            let prefix = format!("{}-", base);
+            let query = #entity_name::find()
+                .select_only()
+                .column(#column_type::#into_variant)
+                .filter(
+                    sea_orm::Condition::any()
+                        .add(#column_type::#into_variant.eq(base.as_str()))
+                        .add(#column_type::#into_variant.like(format!("{}%", prefix)))
+                );
+            #tenant_filter
+            let taken: Vec<Option<String>> = query.into_tuple().all(db).await?;
+            if taken.iter().flatten().any(|slug| *slug == base) {
+                let max_suffix = taken
+                    .iter()
+                    .flatten()
+                    .filter_map(|slug| slug.strip_prefix(prefix.as_str())?.parse::<u64>().ok())
+                    .max()
+                    .unwrap_or(1);
+                format!("{}{}", prefix, max_suffix + 1)
+            } else {
+                base
+            }
+        }
+    } else {
+        quote! { base }
+    };
+    let should_fill = if option_inner_type(into_ty).is_some() {
+        quote! { should_fill_field!(#target.#into_ident) }
+    } else {
+        quote! { should_fill_field!(#target.#into_ident, #into_ty) }
+    };
+    let set_value = active_set_value(into_field, quote! { slug.into() });
+
+    Ok(quote! {
+        if #should_fill {
+            if let Some(source) = #source {
+                // 小写，非字母数字字符替换为 -，并合并连续的 -
+                let transliterated: String = #transliterate;
+                let mut base = String::with_capacity(transliterated.len());
+                for ch in transliterated.chars().flat_map(char::to_lowercase) {
+                    if ch.is_alphanumeric() {
+                        base.push(ch);
+                    } else if !base.is_empty() && !base.ends_with('-') {
+                        base.push('-');
+                    }
+                }
+                let base = base.trim_end_matches('-').to_string();
+                if !base.is_empty() {
+                    let slug: String = { #dedupe };
+                    #target.#into_ident = #set_value;
+                }
+            }
+        }
+    })
+}
+
+/// 生成排序相关方法
+fn generate_sort_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let tenant_id = config.field("tenant_id");
+    let id_column = config.column("id");
+    let tenant_id_column = config.column("tenant_id");
+    let Some((field, is_option)) = sort_field(config, fields)? else {
+        return Ok(quote! {});
+    };
+
+    let entity_name = config.entity_type();
+    let ident = &field.ident;
+    let variant = column_variant(&field.name, ident.span());
+
+    let record_not_found = config.message(Message::RecordNotFound);
+    let target_sort = if is_option {
+        quote! { other.#ident.unwrap_or(0) }
+    } else {
+        quote! { other.#ident }
+    };
+    let tenant_filter = if config.tenant {
+        quote! { .filter(#column_type::#tenant_id_column.eq(record.#tenant_id.clone())) }
+    } else {
+        quote! {}
+    };
+
+    let order_by_sort_query = config.with_sql_comment("order_by_sort", quote! { Self::find() });
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 按排序列升序查询
+            pub fn order_by_sort() -> sea_orm::Select<Self> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryOrder};
+                Self::base_scope(#order_by_sort_query).order_by_asc(#column_type::#variant)
+            }
+
+            /// 在调用方提供的事务中将 id 对应的记录移动到 other_id 之前，other_id 及其之后的记录依次后移；
+            /// 两条记录都必须在 base_scope 范围内且属于同一租户，否则返回 RecordNotFound
+            pub async fn move_before_txn(
+                txn: &sea_orm::DatabaseTransaction,
+                id: &str,
+                other_id: &str,
+            ) -> Result<(), sea_orm::DbErr> {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+                let record = Self::base_scope(Self::find_by_id(id.to_owned()))
+                    .one(txn)
+                    .await?
+                    .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!(#record_not_found, id)))?;
+                let other = Self::base_scope(Self::find_by_id(other_id.to_owned()))
+                    #tenant_filter
+                    .one(txn)
+                    .await?
+                    .ok_or_else(|| sea_orm::DbErr::RecordNotFound(format!(#record_not_found, other_id)))?;
+                let target_sort = #target_sort;
+                let backend = sea_orm::ConnectionTrait::get_database_backend(txn);
+
+                Self::base_scope(Self::batch_update_for(backend))
+                    .col_expr(#column_type::#variant, sea_orm::prelude::Expr::col(#column_type::#variant).add(1))
+                    .filter(#column_type::#variant.gte(target_sort))
+                    .filter(#column_type::#id_column.ne(id))
+                    #tenant_filter
+                    .exec(txn)
+                    .await?;
+                Self::base_scope(Self::batch_update_for(backend))
+                    .col_expr(#column_type::#variant, sea_orm::prelude::Expr::value(target_sort))
+                    .filter(#column_type::#id_column.eq(id))
+                    #tenant_filter
+                    .exec(txn)
+                    .await?;
+                Ok(())
+            }
+
+            /// 开启事务执行 move_before_txn，两次更新一起提交
+            pub async fn move_before<C>(db: &C, id: &str, other_id: &str) -> Result<(), sea_orm::DbErr>
+            where
+                C: sea_orm::TransactionTrait,
+            {
+                let txn = db.begin().await?;
+                Self::move_before_txn(&txn, id, other_id).await?;
+                txn.commit().await
+            }
+        }
+    })
+}
+
+/// 受管列的 DDL 列定义
+fn managed_column_ddl(config: &AutoFieldConfig, column: &str, dialect: SqlDialect) -> &'static str {
+    if config.hash.as_ref().is_some_and(|hash| hash.into == column) {
+        return "CHAR(64) NULL";
+    }
+    let zoned = matches!(config.timestamp_storage, TimestampStorage::Utc | TimestampStorage::WithTimeZone);
+    match (column, dialect) {
+        ("create_time" | "update_time", _) if config.timestamp_storage == TimestampStorage::Millis => "BIGINT NULL",
+        ("update_time", SqlDialect::MySql) if config.timestamps_source == TimestampSource::Database && zoned => {
+            "TIMESTAMP NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP"
+        }
+        ("update_time", SqlDialect::MySql) if config.timestamps_source == TimestampSource::Database => {
+            "DATETIME NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP"
+        }
+        ("create_time" | "update_time", SqlDialect::MySql) if zoned => "TIMESTAMP NULL DEFAULT CURRENT_TIMESTAMP",
+        ("create_time" | "update_time", SqlDialect::Postgres) if zoned => "TIMESTAMPTZ NULL DEFAULT CURRENT_TIMESTAMP",
+        ("id", _) => "VARCHAR(32) NOT NULL PRIMARY KEY",
+        ("create_time" | "update_time", SqlDialect::MySql) => "DATETIME NULL DEFAULT CURRENT_TIMESTAMP",
+        ("expire_time" | "delete_time", SqlDialect::MySql) => "DATETIME NULL",
+        ("expire_time" | "delete_time", SqlDialect::Postgres) => "TIMESTAMP NULL",
+        ("create_time" | "update_time", SqlDialect::Postgres) => "TIMESTAMP NULL DEFAULT CURRENT_TIMESTAMP",
+        ("create_by" | "update_by", _) => "VARCHAR(64) NULL",
+        ("create_id" | "update_id" | "create_proxy_id" | "update_proxy_id" | "tenant_id", _) => "VARCHAR(32) NULL",
+        ("tenant_name", _) => "VARCHAR(128) NULL",
+        ("version", SqlDialect::MySql) => "INT NULL DEFAULT 1",
+        ("version", SqlDialect::Postgres) => "INTEGER NULL DEFAULT 1",
+        ("delete_flag", SqlDialect::MySql) if config.delete_flag_bool => "TINYINT(1) NULL DEFAULT 0",
+        ("delete_flag", SqlDialect::Postgres) if config.delete_flag_bool => "BOOLEAN NULL DEFAULT FALSE",
+        ("delete_flag", SqlDialect::MySql) => "INT NULL DEFAULT 0",
+        ("delete_flag", SqlDialect::Postgres) => "INTEGER NULL DEFAULT 0",
+        _ => "VARCHAR(255) NULL",
+    }
+}
+
+/// 生成受管列的 CREATE TABLE 片段，供 DBA 审阅
+fn generate_managed_ddl(config: &AutoFieldConfig, table_name: &str, dialect: SqlDialect) -> String {
+    let mut definitions: Vec<String> = config
+        .managed_columns()
+        .into_iter()
+        .map(|column| format!("    {} {}", column, managed_column_ddl(config, column, dialect)))
+        .collect();
+
+    // 生成的查询方法会按这些列过滤
+    let mut indexed = Vec::new();
+    if config.tenant {
+        indexed.push("tenant_id");
+    }
+    if config.soft_delete {
+        indexed.push("delete_flag");
+    }
+
+    let mut ddl = format!("-- auto_field managed columns for {}\n", table_name);
+    match dialect {
+        SqlDialect::MySql => {
+            definitions.extend(indexed.iter().map(|column| format!("    INDEX idx_{0} ({0})", column)));
+            ddl.push_str(&format!("CREATE TABLE {} (\n{}\n);\n", table_name, definitions.join(",\n")));
+        }
+        SqlDialect::Postgres => {
+            ddl.push_str(&format!("CREATE TABLE {} (\n{}\n);\n", table_name, definitions.join(",\n")));
+            for column in indexed {
+                ddl.push_str(&format!("CREATE INDEX idx_{0}_{1} ON {0} ({1});\n", table_name, column));
+            }
+        }
+    }
+    ddl
+}
+
+/// 根据生成的查询方法推荐复合索引
+fn generate_index_hints(config: &AutoFieldConfig, table_name: &str) -> String {
+    let mut hints = format!("# auto_field index hints for {}\n", table_name);
+    let mut seen = Vec::new();
+    for (method, filters) in config.query_method_filters() {
+        if filters.is_empty() {
+            continue;
+        }
+        let name = format!("idx_{}_{}", table_name, filters.join("_"));
+        if seen.contains(&name) {
+            continue;
+        }
+        hints.push_str(&format!("{} ({}) -- {}\n", name, filters.join(", "), method));
+        seen.push(name);
+    }
+    hints
+}
+
+/// 生成 ActiveModel::apply_json_patch，启用 json-patch 特性时每个实体都生成
+fn generate_json_patch(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !cfg!(feature = "json-patch") {
+        return Ok(quote! {});
+    }
+
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+    let patch_error = quote! { __auto_field_trait::PatchError };
+
+    // 主键和受管列不接受补丁，其余列按字段名匹配
+    let mut rejected = Vec::new();
+    let mut arms = Vec::new();
+    for field in &fields.fields {
+        let name = &field.name;
+        if managed.contains(&&field.ident) || name == "id" {
+            rejected.push(name);
+            continue;
+        }
+        let ident = &field.ident;
+        let ty = &field.ty;
+        arms.push(quote! {
+            #name => {
+                let value = serde_json::from_value::<#ty>(value.clone()).map_err(|error| #patch_error::InvalidValue {
+                    field: key.clone(),
+                    message: error.to_string(),
+                })?;
+                patched.#ident = sea_orm::ActiveValue::Set(value);
+            }
+        });
+    }
+    let rejected_arm = (!rejected.is_empty()).then(|| {
+        quote! {
+            #(#rejected)|* => return Err(#patch_error::ManagedColumn { column: key.clone() }),
+        }
+    });
+
+    Ok(quote! {
+        impl #active_model_name {
+            /// 按 JSON merge patch 赋值：补丁中出现的列设为对应值，null 设为 NULL，未出现的列保持不变。
+            /// 列的值整体替换，JSON 列不做递归合并。
+            ///
+            /// 补丁不是对象、包含主键或受管列、包含未知字段或取值无法转换时返回错误，且不修改任何列
+            pub fn apply_json_patch(&mut self, patch: &serde_json::Value) -> Result<(), #patch_error> {
+                let serde_json::Value::Object(entries) = patch else {
+                    return Err(#patch_error::NotAnObject);
+                };
+                let mut patched = self.clone();
+                for (key, value) in entries {
+                    match key.as_str() {
+                        #rejected_arm
+                        #(#arms)*
+                        _ => return Err(#patch_error::UnknownField { field: key.clone() }),
+                    }
+                }
+                *self = patched;
+                Ok(())
+            }
+        }
+    })
+}
+
+/// 生成实体注册信息，启用 registry 特性时每个实体都登记到运行时库的实体注册表
+fn generate_registry_entry(
+    config: &AutoFieldConfig,
+    input: &DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !cfg!(feature = "registry") {
+        return Ok(quote! {});
+    }
+
+    let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+        &input.ident,
+        "the `registry` feature requires #[sea_orm(table_name = \"...\")] on the entity"
+    ))?;
+    let columns = config.managed_columns();
+    let snapshot_columns = config.snapshot_columns();
+    let timestamps = config.timestamps;
+    let audit = config.audit;
+    let tenant = config.tenant;
+    let version = config.version;
+    let soft_delete = config.soft_delete;
+
+    Ok(quote! {
+        __auto_field_trait::registry::submit! {
+            __auto_field_trait::registry::EntityDescriptor {
+                entity: module_path!(),
+                table: #table_name,
+                timestamps: #timestamps,
+                audit: #audit,
+                tenant: #tenant,
+                version: #version,
+                soft_delete: #soft_delete,
+                managed_columns: &[#(#columns),*],
+                snapshot_columns: &[#(#snapshot_columns),*],
+            }
+        }
+    })
+}
+
+/// 生成维护任务登记，启用 registry 特性时把 enforce_retention、purge_expired 登记到运行时库的维护任务注册表，
+/// 统一的调度器按注册表执行，无需逐个实体接线
+fn generate_maintenance_tasks(
+    config: &AutoFieldConfig,
+    input: &DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !cfg!(feature = "registry") || (config.retention_seconds.is_none() && config.ttl_seconds.is_none()) {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+        &input.ident,
+        "the `registry` feature requires #[sea_orm(table_name = \"...\")] on the entity"
+    ))?;
+
+    // (任务名, 执行语句)，执行语句返回影响的行数
+    let mut tasks = Vec::new();
+    if config.retention_seconds.is_some() {
+        tasks.push(("enforce_retention", quote! { Self::enforce_retention(db).await }));
+    }
+    if config.ttl_seconds.is_some() {
+        tasks.push(("purge_expired", quote! { Ok(Self::purge_expired(db).await?.rows_affected) }));
+    }
+
+    let mut runners = Vec::new();
+    let mut submits = Vec::new();
+    for (name, run) in tasks {
+        let runner = quote::format_ident!("auto_field_maintenance_{}", name);
+        runners.push(quote! {
+            #[doc(hidden)]
+            pub fn #runner(
+                db: &sea_orm::DatabaseConnection,
+            ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<u64, sea_orm::DbErr>> + Send + '_>> {
+                Box::pin(async move { #run })
+            }
+        });
+        submits.push(quote! {
+            __auto_field_trait::registry::submit! {
+                __auto_field_trait::registry::MaintenanceTask {
+                    entity: module_path!(),
+                    table: #table_name,
+                    name: #name,
+                    run: #entity_name::#runner,
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #entity_name {
+            #(#runners)*
+        }
+
+        #(#submits)*
+    })
+}
+
+/// 生成 ActiveModel::plan_autofill，预演 before_save 会填充哪些字段及其取值，不访问数据库。
+/// 依赖数据库的逻辑按批量插入的方式处理（追加排序不填充、slug 不去重），加密字段不预演
+fn generate_autofill_plan(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let id = config.field("id");
+    let target = quote! { active_model };
+
+    let mut insert_body = vec![should_fill_field_macro()];
+    insert_body.extend(generate_managed_insert_fills(config, &target));
+    insert_body.extend(generate_field_default_fills(fields, &target)?);
+    insert_body.extend(generate_sync_fills(config, fields, &target)?);
+    insert_body.push(generate_sort_fill(config, fields, &target, false)?);
+    insert_body.push(generate_slug_fill(config, fields, &target, false)?);
+    insert_body.extend(generate_pinyin_fills(config, fields, &target)?);
+    insert_body.push(generate_hash_fill(config, fields, &target, true)?);
+
+    let mut update_body = generate_sync_fills(config, fields, &target)?;
+    update_body.extend(generate_pinyin_fills(config, fields, &target)?);
+    update_body.push(generate_hash_fill(config, fields, &target, false)?);
+    update_body.extend(generate_managed_update_fills(config, &target));
+
+    // 雪花 ID 预演时不消耗生成器，只记录占位值
+    let planned_id = if config.snowflake_id {
+        quote! {
+            if insert && matches!(&self.#id, sea_orm::ActiveValue::NotSet) {
+                plan.push("id", ::std::string::String::from("<snowflake id>"));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let planned_fields = fields.fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        quote! {
+            if self.#ident != active_model.#ident {
+                plan.push(#name, format!("{:?}", active_model.#ident));
+            }
+        }
+    });
+
+    let context_capture = config.context_capture();
+    let now_capture = if config.needs_now() {
+        let now_expr = config.now_expr();
+        quote! { let now = #now_expr; }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #active_model_name {
+            /// 预演 before_save 的自动填充，返回将被填充的字段及取值，不修改当前 ActiveModel
+            pub fn plan_autofill(&self, insert: bool) -> __auto_field_trait::AutofillPlan {
+                let mut active_model = self.clone();
+                #context_capture
+                #now_capture
+                if insert {
+                    #(#insert_body)*
+                } else {
+                    #(#update_body)*
+                }
+
+                let mut plan = __auto_field_trait::AutofillPlan::default();
+                #planned_id
+                #(#planned_fields)*
+                plan
+            }
+        }
+    })
+}
+
+/// 生成测试数据工厂，受管字段全部填好，审计、租户字段优先取当前上下文，缺省时使用占位值
+fn generate_factory_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let id = config.field("id");
+    let create_time = config.field("create_time");
+    let update_time = config.field("update_time");
+    if !config.gen_factory {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let fill = quote! { __auto_field_trait::fill };
+    let target = quote! { active_model };
+
+    let mut body = vec![should_fill_field_macro()];
+    if config.snowflake_id {
+        // 没有注册生成器时（单元测试中常见）使用进程内递增序号
+        let next_id = config.next_snowflake_id(&quote! { generator });
+        body.push(quote! {
+            if should_fill_field!(active_model.#id, String) {
+                match #entity_name::auto_field_id_generator() {
+                    Some(generator) => {
+                        let mut generator = generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                        active_model.#id = sea_orm::ActiveValue::Set(#next_id.into());
+                    }
+                    None => {
+                        static SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+                        let id = SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        active_model.#id = sea_orm::ActiveValue::Set(id.to_string().into());
+                    }
+                }
+            }
+        });
+    }
+    body.extend(generate_managed_insert_fills(config, &target));
+    // 数据库维护时间戳时工厂也直接填充，保证 NOT NULL 约束满足
+    if config.timestamps && !config.fills_timestamps() {
+        let now_value = config.timestamp_value(quote! { now });
+        body.push(quote! {
+            #fill::fill_unset(&mut active_model.#create_time, #now_value);
+            #fill::fill_unset(&mut active_model.#update_time, #now_value);
+        });
+    }
+    let mut placeholders = Vec::new();
+    if config.audit {
+        placeholders.extend(["create_by", "create_id", "update_by", "update_id"]);
+    }
+    if config.tenant {
+        placeholders.extend(["tenant_id", "tenant_name"]);
+    }
+    for column in placeholders {
+        let ident = config.field(column);
+        body.push(quote! {
+            #fill::fill_unset(&mut active_model.#ident, ::std::string::String::from("factory").into());
+        });
+    }
+    body.extend(generate_field_default_fills(fields, &target)?);
+
+    let context_capture = config.context_capture();
+    let now_capture = if config.needs_now() || config.timestamps {
+        let now_expr = config.now_expr();
+        quote! { let now = #now_expr; }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 测试数据工厂，返回受管字段全部填好的 ActiveModel
+            pub fn factory() -> #active_model_name {
+                Self::factory_with(|_| {})
+            }
+
+            /// 测试数据工厂，先应用 overrides，再填充仍未赋值的受管字段和字段默认值
+            pub fn factory_with<F>(overrides: F) -> #active_model_name
+            where
+                F: FnOnce(&mut #active_model_name),
+            {
+                let mut active_model = <#active_model_name as sea_orm::ActiveModelTrait>::default();
+                overrides(&mut active_model);
+                #context_capture
+                #now_capture
+                #(#body)*
+                active_model
+            }
+        }
+    })
+}
+
+/// 生成 ModelBuilder：只为非受管列生成 setter，build() 检查必填业务列，受管列保持 NotSet 交给 before_save 填充
+fn generate_builder_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_builder {
+        return Ok(quote! {});
+    }
+
+    let builder_name = syn::Ident::new("ModelBuilder", struct_name.span());
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+    let derived = config.derived_columns();
+
+    let mut setters = Vec::new();
+    let mut required_checks = Vec::new();
+    for field in &fields.fields {
+        if managed.contains(&&field.ident) {
+            continue;
+        }
+        let ident = &field.ident;
+        let ty = &field.ty;
+        setters.push(quote! {
+            pub fn #ident(mut self, value: impl Into<#ty>) -> Self {
+                self.active_model.#ident = sea_orm::ActiveValue::Set(value.into());
+                self
+            }
+        });
+
+        // 可空列、有默认值的列和派生列不是必填项
+        let optional = option_inner_type(ty).is_some()
+            || field.config.default.is_some()
+            || derived.contains(&field.name.as_str())
+            || derived.contains(&field.column.as_str());
+        if !optional {
+            let name = &field.name;
+            required_checks.push(quote! {
+                if matches!(self.active_model.#ident, sea_orm::ActiveValue::NotSet) {
+                    missing.push(#name);
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        /// 只暴露业务列的构造器，审计、租户等受管列无法手动赋值
+        #[derive(Clone, Debug)]
+        pub struct #builder_name {
+            active_model: #active_model_name,
+        }
+
+        impl #builder_name {
+            #(#setters)*
+
+            /// 生成 ActiveModel，受管列保持 NotSet 由 before_save 填充；缺少必填业务列时返回这些列名
+            pub fn build(self) -> Result<#active_model_name, Vec<&'static str>> {
+                #[allow(unused_mut)]
+                let mut missing = Vec::new();
+                #(#required_checks)*
+                if missing.is_empty() {
+                    Ok(self.active_model)
+                } else {
+                    Err(missing)
+                }
+            }
+        }
+
+        impl #struct_name {
+            /// 创建 ModelBuilder
+            pub fn builder() -> #builder_name {
+                #builder_name {
+                    // 经由 ActiveModelBehavior::new，保留 active_model_defaults 的预置值
+                    active_model: <#active_model_name as ::std::default::Default>::default(),
+                }
+            }
+        }
+    })
+}
+
+/// 生成部分更新载荷 UpdatePayload：主键必填，业务列为 None 时保持 NotSet，受管列不在载荷中，
+/// 转换为 ActiveModel 后受管列始终为 NotSet，交给 before_save 维护
+fn generate_update_payload(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_update_payload {
+        return Ok(quote! {});
+    }
+
+    let payload_name = syn::Ident::new("UpdatePayload", struct_name.span());
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+    let derives = &config.update_payload_derive;
+    let id_field = fields.get("id");
+
+    let mut payload_fields = Vec::new();
+    let mut assigns = Vec::new();
+    if let Some(field) = id_field {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        payload_fields.push(quote! {
+            /// 主键，决定更新哪一行
+            pub #ident: #ty
+        });
+        assigns.push(quote! { active_model.#ident = sea_orm::ActiveValue::Set(self.#ident); });
+    }
+    for field in &fields.fields {
+        if managed.contains(&&field.ident) || id_field.is_some_and(|id| id.ident == field.ident) {
+            continue;
+        }
+        let ident = &field.ident;
+        let ty = &field.ty;
+        payload_fields.push(quote! { pub #ident: Option<#ty> });
+        assigns.push(quote! {
+            if let Some(value) = self.#ident {
+                active_model.#ident = sea_orm::ActiveValue::Set(value);
+            }
+        });
+    }
+
+    Ok(quote! {
+        /// 部分更新载荷，字段为 None 时不更新对应列；审计、租户等受管列不在载荷中
+        #[derive(Clone, Debug, PartialEq #(, #derives)*)]
+        pub struct #payload_name {
+            #(#payload_fields,)*
+        }
+
+        impl sea_orm::IntoActiveModel<#active_model_name> for #payload_name {
+            /// 转换为只设置了主键和载荷中非 None 列的 ActiveModel，其余列（包括全部受管列）保持 NotSet
+            fn into_active_model(self) -> #active_model_name {
+                // 不经过 ActiveModelBehavior::new，active_model_defaults 的预置值不会混入部分更新
+                #[allow(unused_mut)]
+                let mut active_model = <#active_model_name as sea_orm::ActiveModelTrait>::default();
+                #(#assigns)*
+                active_model
+            }
+        }
+    })
+}
+
+/// 生成 ActiveModel::from_row：按表头把一行文本导入业务列，受管列留给 before_save 填充
+fn generate_import_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_import {
+        return Ok(quote! {});
+    }
+
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+    let import_error = quote! { __auto_field_trait::ImportError };
+
+    // 表头可以是字段名或数据库列名；受管列的表头（如导出文件中的 id、create_time）直接忽略
+    let mut ignored = std::collections::BTreeSet::new();
+    let mut arms = Vec::new();
+    for field in &fields.fields {
+        let headers: std::collections::BTreeSet<&str> = [field.name.as_str(), field.column.as_str()].into_iter().collect();
+        if managed.contains(&&field.ident) {
+            ignored.extend(headers);
+            continue;
+        }
+        let ident = &field.ident;
+        let parse = |ty: &syn::Type| {
+            quote! {
+                <#ty as std::str::FromStr>::from_str(value).map_err(|error| #import_error::InvalidValue {
+                    column: header.to_owned(),
+                    message: error.to_string(),
+                })?
+            }
+        };
+        // 可空列的空单元格导入为 NULL
+        let value = match option_inner_type(&field.ty) {
+            Some(inner) => {
+                let parse = parse(inner);
+                quote! { if value.is_empty() { None } else { Some(#parse) } }
+            }
+            None => parse(&field.ty),
+        };
+        let headers = headers.into_iter();
+        arms.push(quote! {
+            #(#headers)|* => active_model.#ident = sea_orm::ActiveValue::Set(#value),
+        });
+    }
+    let ignored_arm = (!ignored.is_empty()).then(|| {
+        let ignored = ignored.into_iter();
+        quote! { #(#ignored)|* => {} }
+    });
+
+    Ok(quote! {
+        impl #active_model_name {
+            /// 按表头导入一行，如 CSV、Excel 的一行，业务列的文本经 FromStr 转换为字段类型，可空列的空单元格为 NULL。
+            /// 受管列的表头被忽略，这些列保持 NotSet 由 before_save 填充；未出现的业务列保留 ActiveModel::default() 的值
+            ///
+            /// 表头与取值数量不一致、出现未知表头或取值无法转换时返回错误
+            pub fn from_row<H, V>(headers: &[H], values: &[V]) -> Result<Self, #import_error>
+            where
+                H: AsRef<str>,
+                V: AsRef<str>,
+            {
+                if headers.len() != values.len() {
+                    return Err(#import_error::LengthMismatch {
+                        headers: headers.len(),
+                        values: values.len(),
+                    });
+                }
+                let mut active_model = <Self as ::std::default::Default>::default();
+                for (header, value) in headers.iter().zip(values) {
+                    let header = header.as_ref().trim();
+                    let value = value.as_ref();
+                    match header {
+                        #ignored_arm
+                        #(#arms)*
+                        _ => return Err(#import_error::UnknownColumn { column: header.to_owned() }),
+                    }
+                }
+                Ok(active_model)
+            }
+        }
+    })
+}
+
+/// 生成 Model::export_record：按字段顺序导出 (表头, 值)，删除标记、租户等内部受管列不导出
+fn generate_export_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_export {
+        if let Some(field) = fields.fields.iter().find(|field| field.config.export_header.is_some() || field.config.export_skip) {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "export_header and export_skip require gen_export to be enabled"
+            ));
+        }
+        return Ok(quote! {});
+    }
+
+    let internal: Vec<syn::Ident> = config.internal_columns().into_iter().map(|column| config.field(column)).collect();
+    let mut headers = Vec::new();
+    let mut values = Vec::new();
+    for field in &fields.fields {
+        if field.config.export_skip || internal.contains(&field.ident) {
+            continue;
+        }
+        let ident = &field.ident;
+        headers.push(field.config.export_header.clone().unwrap_or_else(|| field.column.clone()));
+        // 可空列的 NULL 导出为空字符串
+        values.push(if option_inner_type(&field.ty).is_some() {
+            quote! { self.#ident.as_ref().map(ToString::to_string).unwrap_or_default() }
+        } else {
+            quote! { self.#ident.to_string() }
+        });
+    }
+    let record_headers = headers.clone();
+
+    Ok(quote! {
+        impl #struct_name {
+            /// 导出的表头，顺序与 export_record 一致
+            pub const EXPORT_HEADERS: &'static [&'static str] = &[#(#headers),*];
+
+            /// 导出用的 (表头, 值) 列表，按字段声明顺序排列，不含删除标记、租户、版本号和行哈希等内部列；
+            /// 值按 Display 格式化，NULL 为空字符串
+            pub fn export_record(&self) -> Vec<(String, String)> {
+                vec![#((#record_headers.to_owned(), #values)),*]
+            }
+        }
+    })
+}
+
+/// 生成不依赖 ActiveModel 的填充函数，以 (Column, SimpleExpr) 列值对返回插入、更新时的受管列取值，
+/// 供直接拼装 sea-query 语句的代码沿用同一套约定
+fn generate_sea_query_values(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    if !config.gen_sea_query {
+        return quote! {};
+    }
+
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let column = |name: &str| {
+        let variant = config.column(name);
+        quote! { #column_type::#variant }
+    };
+    let value = quote! { sea_orm::sea_query::Expr::value };
+    let operator_present = operator_present();
+    let mut insert_values = Vec::new();
+    let mut update_values = Vec::new();
+
+    if config.snowflake_id {
+        let id = column("id");
+        let next_id = config.next_snowflake_id(&quote! { generator });
+        let generate = if config.id_panic_guard {
+            quote! {
+                if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #next_id)) {
+                    values.push((#id, #value(id)));
+                }
+            }
+        } else {
+            quote! { values.push((#id, #value(#next_id))); }
+        };
+        insert_values.push(quote! {
+            if let Some(generator) = Self::auto_field_id_generator() {
+                let mut generator = generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                #generate
+            }
+        });
+    }
+
+    if config.fills_timestamps() {
+        let create_time = column("create_time");
+        let update_time = column("update_time");
+        let now_value = config.timestamp_value(quote! { now });
+        insert_values.push(quote! {
+            values.push((#create_time, #value(Some(#now_value))));
+            values.push((#update_time, #value(Some(#now_value))));
+        });
+        update_values.push(quote! { values.push((#update_time, #value(Some(#now_value)))); });
+    }
+
+    if config.audit {
+        let mut insert_columns = vec![("create_by", quote! { user_name }), ("create_id", quote! { user_id })];
+        if config.init_update_fields_on_insert {
+            insert_columns.extend([("update_by", quote! { user_name }), ("update_id", quote! { user_id })]);
+        }
+        let insert_pushes = insert_columns.into_iter().map(|(name, source)| {
+            let column = column(name);
+            quote! { values.push((#column, #value(context.#source.clone()))); }
+        });
+        let (create_proxy, update_proxy) = if config.impersonation {
+            let create_proxy_id = column("create_proxy_id");
+            let update_proxy_id = column("update_proxy_id");
+            let init_update_proxy = config.init_update_fields_on_insert.then(|| quote! {
+                values.push((#update_proxy_id, #value(context.acting_user_id.clone())));
+            });
+            (
+                quote! {
+                    if context.acting_user_id.is_some() {
+                        values.push((#create_proxy_id, #value(context.acting_user_id.clone())));
+                        #init_update_proxy
+                    }
+                },
+                quote! { values.push((#update_proxy_id, #value(context.acting_user_id.clone()))); },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+        insert_values.push(quote! {
+            if #operator_present {
+                #(#insert_pushes)*
+                #create_proxy
+            }
+        });
+        let update_by = column("update_by");
+        let update_id = column("update_id");
+        update_values.push(quote! {
+            if #operator_present {
+                values.push((#update_by, #value(context.user_name.clone())));
+                values.push((#update_id, #value(context.user_id.clone())));
+                #update_proxy
+            }
+        });
+    }
+
+    if config.tenant {
+        let tenant_id = column("tenant_id");
+        let tenant_name = column("tenant_name");
+        insert_values.push(quote! {
+            if let Some(tenant_id) = context.tenant_id.clone().filter(|tenant_id| !tenant_id.is_empty()) {
+                values.push((#tenant_id, #value(Some(tenant_id))));
+                if let Some(tenant_name) = context.tenant_name.clone().filter(|tenant_name| !tenant_name.is_empty()) {
+                    values.push((#tenant_name, #value(Some(tenant_name))));
+                }
+            }
+        });
+    }
+
+    if config.version {
+        let version = column("version");
+        insert_values.push(quote! { values.push((#version, #value(Some(1)))); });
+        update_values.push(quote! {
+            values.push((#version, sea_orm::sea_query::Expr::col(#version).add(1)));
+        });
+    }
+
+    if config.soft_delete {
+        let delete_flag = column("delete_flag");
+        let not_deleted = config.not_deleted_value();
+        insert_values.push(quote! { values.push((#delete_flag, #value(Some(#not_deleted)))); });
+    }
+
+    if let Some(ttl_seconds) = config.ttl_seconds {
+        let expire_time = column("expire_time");
+        insert_values.push(quote! {
+            values.push((#expire_time, #value(Some(now + chrono::Duration::seconds(#ttl_seconds)))));
+        });
+    }
+
+    let current_context = config.current_context();
+    let now_expr = config.now_expr();
+    let insert_capture = match (config.needs_context(), config.needs_now()) {
+        (true, true) => quote! { #current_context let now = #now_expr; },
+        (true, false) => current_context.clone(),
+        (false, true) => quote! { let now = #now_expr; },
+        (false, false) => quote! {},
+    };
+    let update_capture = match (config.audit, config.fills_timestamps()) {
+        (true, true) => quote! { #current_context let now = #now_expr; },
+        (true, false) => current_context,
+        (false, true) => quote! { let now = #now_expr; },
+        (false, false) => quote! {},
+    };
+
+    quote! {
+        impl #entity_name {
+            /// 插入时由约定填充的列值对，按当前上下文和时间计算，供直接构造 sea-query INSERT 语句使用；
+            /// 上下文缺少操作人或租户时不包含对应的列
+            pub fn auto_insert_values() -> Vec<(#column_type, sea_orm::sea_query::SimpleExpr)> {
+                #insert_capture
+                #[allow(unused_mut)]
+                let mut values = Vec::new();
+                #(#insert_values)*
+                values
+            }
+
+            /// 更新时由约定写入的列值对，版本号为 `version = version + 1` 表达式，
+            /// 供直接构造 sea-query UPDATE 语句使用
+            pub fn auto_update_values() -> Vec<(#column_type, sea_orm::sea_query::SimpleExpr)> {
+                #update_capture
+                #[allow(unused_mut)]
+                let mut values = Vec::new();
+                #(#update_values)*
+                values
+            }
+        }
+    }
+}
+
+/// 生成 Diesel 变更集 AutoFieldChangeset，启用 diesel 特性且配置了 diesel_table 时生成。
+/// 字段为各受管列，取值为 None 的列在插入和更新时都被跳过，与 SeaORM 实体共用同一套填充约定
+fn generate_diesel_changeset(config: &AutoFieldConfig, fields: &EntityFields) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(diesel_table) = &config.diesel_table else {
+        return Ok(quote! {});
+    };
+
+    // 行哈希由业务列计算，变更集中没有业务列，不包含
+    let hash_column = config.hash.as_ref().map(|hash| hash.into.as_str());
+    let mut changeset_fields = Vec::new();
+    for column in config.managed_columns().into_iter().filter(|column| Some(*column) != hash_column) {
+        let field = fields.get(column).ok_or_else(|| syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("diesel_table is set but the struct has no `{}` field", column)
+        ))?;
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let ty = if option_inner_type(ty).is_some() {
+            quote! { #ty }
+        } else {
+            quote! { Option<#ty> }
+        };
+        let column_name = (field.name != field.column).then(|| {
+            let column_name = syn::Ident::new(&field.column, ident.span());
+            quote! { #[diesel(column_name = #column_name)] }
+        });
+        changeset_fields.push(quote! {
+            #column_name
+            pub #ident: #ty,
+        });
+    }
+
+    let operator_present = operator_present();
+    let mut insert_fills = Vec::new();
+    let mut update_fills = Vec::new();
+
+    if config.snowflake_id {
+        let id = config.field("id");
+        let entity_name = config.entity_type();
+        let next_id = config.next_snowflake_id(&quote! { generator });
+        let generate = if config.id_panic_guard {
+            quote! {
+                if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #next_id)) {
+                    changeset.#id = Some(id.into());
+                }
+            }
+        } else {
+            quote! { changeset.#id = Some(#next_id.into()); }
+        };
+        insert_fills.push(quote! {
+            if let Some(generator) = #entity_name::auto_field_id_generator() {
+                let mut generator = generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                #generate
+            }
+        });
+    }
+
+    if config.fills_timestamps() {
+        let create_time = config.field("create_time");
+        let update_time = config.field("update_time");
+        let now_value = config.timestamp_value(quote! { now });
+        insert_fills.push(quote! {
+            changeset.#create_time = Some(#now_value);
+            changeset.#update_time = Some(#now_value);
+        });
+        update_fills.push(quote! { self.#update_time = Some(#now_value); });
+    }
+
+    if config.audit {
+        let create_by = config.field("create_by");
+        let create_id = config.field("create_id");
+        let update_by = config.field("update_by");
+        let update_id = config.field("update_id");
+        let init_update = config.init_update_fields_on_insert.then(|| quote! {
+            changeset.#update_by = context.user_name.clone().map(Into::into);
+            changeset.#update_id = context.user_id.clone().map(Into::into);
+        });
+        let (create_proxy, update_proxy) = if config.impersonation {
+            let create_proxy_id = config.field("create_proxy_id");
+            let update_proxy_id = config.field("update_proxy_id");
+            let init_update_proxy = config.init_update_fields_on_insert.then(|| quote! {
+                changeset.#update_proxy_id = context.acting_user_id.clone().map(Into::into);
+            });
+            (
+                quote! {
+                    changeset.#create_proxy_id = context.acting_user_id.clone().map(Into::into);
+                    #init_update_proxy
+                },
+                // 未代操作时变更集无法把列写为 NULL，保留原值
+                quote! { self.#update_proxy_id = context.acting_user_id.clone().map(Into::into); },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+        insert_fills.push(quote! {
+            if #operator_present {
+                changeset.#create_by = context.user_name.clone().map(Into::into);
+                changeset.#create_id = context.user_id.clone().map(Into::into);
+                #init_update
+                #create_proxy
+            }
+        });
+        update_fills.push(quote! {
+            if #operator_present {
+                self.#update_by = context.user_name.clone().map(Into::into);
+                self.#update_id = context.user_id.clone().map(Into::into);
+                #update_proxy
+            }
+        });
+    }
+
+    if config.tenant {
+        let tenant_id = config.field("tenant_id");
+        let tenant_name = config.field("tenant_name");
+        insert_fills.push(quote! {
+            if let Some(tenant_id) = context.tenant_id.clone().filter(|tenant_id| !tenant_id.is_empty()) {
+                changeset.#tenant_id = Some(tenant_id.into());
+                changeset.#tenant_name = context.tenant_name.clone().filter(|tenant_name| !tenant_name.is_empty()).map(Into::into);
+            }
+        });
+    }
+
+    if config.version {
+        let version = config.field("version");
+        insert_fills.push(quote! { changeset.#version = Some(1); });
+        update_fills.push(quote! { self.#version = self.#version.map(|version| version + 1); });
+    }
+
+    if config.soft_delete {
+        let delete_flag = config.field("delete_flag");
+        let not_deleted = config.not_deleted_value();
+        insert_fills.push(quote! { changeset.#delete_flag = Some(#not_deleted); });
+    }
+
+    if let Some(ttl_seconds) = config.ttl_seconds {
+        let expire_time = config.field("expire_time");
+        insert_fills.push(quote! {
+            changeset.#expire_time = Some(now + chrono::Duration::seconds(#ttl_seconds));
+        });
+    }
+
+    let current_context = config.current_context();
+    let now_expr = config.now_expr();
+    let insert_capture = match (config.needs_context(), config.needs_now()) {
+        (true, true) => quote! { #current_context let now = #now_expr; },
+        (true, false) => current_context.clone(),
+        (false, true) => quote! { let now = #now_expr; },
+        (false, false) => quote! {},
+    };
+    let update_capture = match (config.audit, config.fills_timestamps()) {
+        (true, true) => quote! { #current_context let now = #now_expr; },
+        (true, false) => current_context,
+        (false, true) => quote! { let now = #now_expr; },
+        (false, false) => quote! {},
+    };
+
+    // 启用 version 时 for_update 必须传入读取到的版本号，变更集写入加 1 后的值，避免漏掉乐观锁
+    let for_update = match fields.get("version").filter(|_| config.version) {
+        Some(field) => {
+            let version = config.field("version");
+            let version_type = option_inner_type(&field.ty).unwrap_or(&field.ty);
+            quote! {
+                /// 更新时的受管列取值，version 为读取到的版本号，变更集中写入 version + 1。
+                /// 更新语句需要同时以读取到的版本号作为条件，受影响行数为 0 即表示发生并发修改
+                pub fn for_update(version: #version_type) -> Self {
+                    let mut changeset = Self {
+                        #version: Some(version),
+                        ..Self::default()
+                    };
+                    changeset.apply_update_conventions();
+                    changeset
+                }
+            }
+        }
+        None => quote! {
+            /// 更新时的受管列取值，等同于对空变更集调用 apply_update_conventions
+            pub fn for_update() -> Self {
+                let mut changeset = Self::default();
+                changeset.apply_update_conventions();
+                changeset
+            }
+        },
+    };
+
+    Ok(quote! {
+        /// 受管列的 Diesel 变更集，取值为 None 的列在插入和更新时都被跳过
+        #[derive(Debug, Clone, Default, PartialEq, diesel::Insertable, diesel::AsChangeset)]
+        #[diesel(table_name = #diesel_table)]
+        pub struct AutoFieldChangeset {
+            #(#changeset_fields)*
+        }
+
+        impl AutoFieldChangeset {
+            /// 插入时的受管列取值，按当前上下文和时间计算，与业务列的 Insertable 一起插入
+            pub fn for_insert() -> Self {
+                #insert_capture
+                #[allow(unused_mut)]
+                let mut changeset = Self::default();
+                #(#insert_fills)*
+                changeset
+            }
+
+            #for_update
+
+            /// 写入更新时间、更新人；版本号字段已有读取值时加 1，需要先把读取到的版本号写入变更集
+            pub fn apply_update_conventions(&mut self) {
+                #update_capture
+                #(#update_fills)*
+            }
+        }
+    })
+}
+
+/// 生成 BSON 文档填充方法，启用 bson 特性时每个实体都生成。文档键为字段名，
+/// 与 Model 经 serde 序列化得到的文档一致
+fn generate_bson_fill(config: &AutoFieldConfig, fields: &EntityFields) -> syn::Result<proc_macro2::TokenStream> {
+    if !cfg!(feature = "bson") {
+        return Ok(quote! {});
+    }
+
+    let key = |column: &str| -> syn::Result<String> {
+        fields.get(column).map(|field| field.name.clone()).ok_or_else(|| syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("the `bson` feature requires a `{}` field", column)
+        ))
+    };
+    let value = |expr: proc_macro2::TokenStream| quote! {
+        bson::to_bson(&#expr).unwrap_or(bson::Bson::Null)
+    };
+    let operator_present = operator_present();
+    let mut insert_fills = Vec::new();
+    let mut update_fills = Vec::new();
+
+    if config.snowflake_id {
+        let id = key("id")?;
+        let next_id = config.next_snowflake_id(&quote! { generator });
+        let generate = if config.id_panic_guard {
+            quote! {
+                if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #next_id)) {
+                    doc.insert(#id, id);
+                }
+            }
+        } else {
+            quote! { doc.insert(#id, #next_id); }
+        };
+        // 文档可能来自已入库的行，已有 id 时保留
+        insert_fills.push(quote! {
+            let missing_id = match doc.get(#id) {
+                None | Some(bson::Bson::Null) => true,
+                Some(bson::Bson::String(id)) => id.is_empty(),
+                Some(_) => false,
+            };
+            if missing_id {
+                if let Some(generator) = Self::auto_field_id_generator() {
+                    let mut generator = generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    #generate
+                }
+            }
+        });
+    }
+
+    if config.fills_timestamps() {
+        let create_time = key("create_time")?;
+        let update_time = key("update_time")?;
+        let now_value = value(config.timestamp_value(quote! { now }));
+        insert_fills.push(quote! {
+            doc.insert(#create_time, #now_value);
+            doc.insert(#update_time, #now_value);
+        });
+        update_fills.push(quote! { doc.insert(#update_time, #now_value); });
+    }
+
+    if config.audit {
+        let mut insert_keys = vec![(key("create_by")?, quote! { user_name }), (key("create_id")?, quote! { user_id })];
+        if config.init_update_fields_on_insert {
+            insert_keys.extend([(key("update_by")?, quote! { user_name }), (key("update_id")?, quote! { user_id })]);
+        }
+        let insert_inserts = insert_keys.into_iter().map(|(key, source)| {
+            let source = value(quote! { context.#source });
+            quote! { doc.insert(#key, #source); }
+        });
+        let acting_user_id = value(quote! { context.acting_user_id });
+        let (create_proxy, update_proxy) = if config.impersonation {
+            let create_proxy_id = key("create_proxy_id")?;
+            let update_proxy_id = key("update_proxy_id")?;
+            let init_update_proxy = config.init_update_fields_on_insert.then(|| quote! {
+                doc.insert(#update_proxy_id, #acting_user_id);
+            });
+            (
+                quote! {
+                    doc.insert(#create_proxy_id, #acting_user_id);
+                    #init_update_proxy
+                },
+                quote! { doc.insert(#update_proxy_id, #acting_user_id); },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+        let update_by = key("update_by")?;
+        let update_id = key("update_id")?;
+        let user_name = value(quote! { context.user_name });
+        let user_id = value(quote! { context.user_id });
+        insert_fills.push(quote! {
+            if #operator_present {
+                #(#insert_inserts)*
+                #create_proxy
+            }
+        });
+        update_fills.push(quote! {
+            if #operator_present {
+                doc.insert(#update_by, #user_name);
+                doc.insert(#update_id, #user_id);
+                #update_proxy
+            }
+        });
+    }
+
+    if config.tenant {
+        let tenant_id = key("tenant_id")?;
+        let tenant_name = key("tenant_name")?;
+        insert_fills.push(quote! {
+            if let Some(tenant_id) = context.tenant_id.clone().filter(|tenant_id| !tenant_id.is_empty()) {
+                doc.insert(#tenant_id, tenant_id);
+                if let Some(tenant_name) = context.tenant_name.clone().filter(|tenant_name| !tenant_name.is_empty()) {
+                    doc.insert(#tenant_name, tenant_name);
+                }
+            }
+        });
+    }
+
+    if config.version {
+        let version = key("version")?;
+        insert_fills.push(quote! { doc.insert(#version, 1_i32); });
+        // 保持文档中原有的整数宽度
+        update_fills.push(quote! {
+            match doc.get(#version) {
+                Some(bson::Bson::Int32(version)) => { let version = *version + 1; doc.insert(#version, version); }
+                Some(bson::Bson::Int64(version)) => { let version = *version + 1; doc.insert(#version, version); }
+                _ => {}
+            }
+        });
+    }
+
+    if config.soft_delete {
+        let delete_flag = key("delete_flag")?;
+        let not_deleted = value(config.not_deleted_value());
+        insert_fills.push(quote! { doc.insert(#delete_flag, #not_deleted); });
+    }
+
+    if let Some(ttl_seconds) = config.ttl_seconds {
+        let expire_time = key("expire_time")?;
+        let expire_value = value(quote! { (now + chrono::Duration::seconds(#ttl_seconds)) });
+        insert_fills.push(quote! { doc.insert(#expire_time, #expire_value); });
+    }
+
+    let entity_name = config.entity_type();
+    let normalize_context = config.normalize_context();
+    let now_expr = config.now_expr();
+    let context_param = if config.needs_context() {
+        quote! { context }
+    } else {
+        quote! { _context }
+    };
+    let update_context_param = if config.audit {
+        quote! { context }
+    } else {
+        quote! { _context }
+    };
+    let insert_doc = if insert_fills.is_empty() { quote! { _doc } } else { quote! { doc } };
+    let update_doc = if update_fills.is_empty() { quote! { _doc } } else { quote! { doc } };
+    let insert_now = config.needs_now().then(|| quote! { let now = #now_expr; });
+    let update_now = config.fills_timestamps().then(|| quote! { let now = #now_expr; });
+    let insert_normalize = config.needs_context().then(|| quote! {
+        #[allow(unused_mut)]
+        let mut context = context.clone();
+        #normalize_context
+    });
+    let update_normalize = config.audit.then(|| quote! {
+        #[allow(unused_mut)]
+        let mut context = context.clone();
+        #normalize_context
+    });
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 按插入约定填充 BSON 文档中的受管字段，上下文由调用方传入；文档中已有 id 时保留，
+            /// 上下文缺少操作人或租户时不修改对应字段
+            pub fn fill_for_insert(#insert_doc: &mut bson::Document, #context_param: &__auto_field_trait::AutoFieldContext) {
+                #insert_normalize
+                #insert_now
+                #(#insert_fills)*
+            }
+
+            /// 按更新约定填充 BSON 文档中的更新时间、更新人，文档中已有整数版本号时加 1
+            pub fn fill_for_update(#update_doc: &mut bson::Document, #update_context_param: &__auto_field_trait::AutoFieldContext) {
+                #update_normalize
+                #update_now
+                #(#update_fills)*
+            }
+        }
+    })
+}
+
+/// 生成变更事件载荷 XxxChangedEvent（Xxx 为表名的大驼峰形式）和 Model::to_change_event，
+/// 统一保存后发布到消息队列的事件结构
+fn generate_change_event(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    input: &DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_change_event {
+        return Ok(quote! {});
+    }
+
+    let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+        &input.ident,
+        "gen_change_event requires #[sea_orm(table_name = \"...\")] on the entity"
+    ))?;
+    let id_field = fields.get("id").ok_or_else(|| syn::Error::new_spanned(
+        &input.ident,
+        "gen_change_event requires an `id` field"
+    ))?;
+    let table_name = table_name.rsplit('.').next().unwrap_or(&table_name);
+    let event_name = quote::format_ident!("{}ChangedEvent", column_variant(table_name, struct_name.span()));
+    let id = &id_field.ident;
+    let id_ty = &id_field.ty;
+    let change_op = quote! { __auto_field_trait::ChangeOp };
+
+    let tenant_id = if config.tenant {
+        let tenant_id = config.field("tenant_id");
+        match fields.get("tenant_id") {
+            Some(field) if option_inner_type(&field.ty).is_some() => quote! {
+                self.#tenant_id.as_ref().map(|tenant_id| tenant_id.to_string())
+            },
+            _ => quote! { Some(self.#tenant_id.to_string()) },
+        }
+    } else {
+        quote! { None }
+    };
+
+    // 插入、更新事件取行上的更新时间，与库中记录一致；删除事件和无更新时间时取当前时间
+    let now_expr = config.now_expr();
+    let changed_at = if config.timestamps {
+        let update_time = config.field("update_time");
+        let updated_at = config.timestamp_to_utc(quote! { time });
+        quote! {
+            match op {
+                #change_op::Delete => None,
+                _ => self.#update_time.and_then(|time| #updated_at),
+            }
+            .unwrap_or_else(|| (#now_expr).and_utc())
+        }
+    } else {
+        quote! { (#now_expr).and_utc() }
+    };
+
+    // 差异只比较业务列，受管列、加密列和个人信息列不进入事件
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+    let diffs = fields.fields.iter()
+        .filter(|field| !managed.contains(&&field.ident) && !field.config.encrypt && !field.config.pii)
+        .map(|field| {
+            let ident = &field.ident;
+            let name = &field.name;
+            quote! {
+                let after = serde_json::to_value(&self.#ident).unwrap_or(serde_json::Value::Null);
+                let previous = serde_json::to_value(&before.#ident).unwrap_or(serde_json::Value::Null);
+                if previous != after {
+                    diff.insert(#name.to_string(), serde_json::json!({ "before": previous, "after": after }));
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let before = if diffs.is_empty() { quote! { _before } } else { quote! { before } };
+    let current_context = config.current_context();
+
+    Ok(quote! {
+        /// 保存后发布的变更事件，字段与其他实体的事件一致，diff 仅在带差异构造时出现
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        pub struct #event_name {
+            pub id: #id_ty,
+            pub op: #change_op,
+            pub tenant_id: Option<String>,
+            pub changed_at: chrono::DateTime<chrono::Utc>,
+            /// 操作人 ID，取自当前上下文
+            pub actor: Option<String>,
+            /// 变更的业务字段，键为字段名，值为 {"before": ..., "after": ...}
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub diff: Option<serde_json::Map<String, serde_json::Value>>,
+        }
+
+        impl #struct_name {
+            /// 构造当前记录的变更事件，不包含差异
+            pub fn to_change_event(&self, op: #change_op) -> #event_name {
+                #current_context
+                #event_name {
+                    id: self.#id.clone(),
+                    op,
+                    tenant_id: #tenant_id,
+                    changed_at: #changed_at,
+                    actor: context.user_id.clone().filter(|user_id| !user_id.is_empty()),
+                    diff: None,
+                }
+            }
+
+            /// 构造变更事件并附带与 before 相比变化的业务字段
+            pub fn to_change_event_with_diff(&self, op: #change_op, #before: &Self) -> #event_name {
+                #[allow(unused_mut)]
+                let mut diff = serde_json::Map::new();
+                #(#diffs)*
+                let mut event = self.to_change_event(op);
+                event.diff = Some(diff);
+                event
+            }
+        }
+    })
+}
+
+/// 生成 Model::to_cdc_envelope，按 Debezium 信封格式输出应用层变更，行镜像的键为数据库列名，
+/// 下游按数据库 CDC 解析的作业无需区分来源
+fn generate_cdc_envelope(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    input: &DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !config.gen_cdc_envelope {
+        return Ok(quote! {});
+    }
+
+    let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+        &input.ident,
+        "gen_cdc_envelope requires #[sea_orm(table_name = \"...\")] on the entity"
+    ))?;
+    let change_op = quote! { __auto_field_trait::ChangeOp };
+    let connector_version = env!("CARGO_PKG_VERSION");
+
+    // 行镜像与库中存储一致，加密列保持密文
+    let columns = fields.fields.iter().map(|field| {
+        let ident = &field.ident;
+        let column = &field.column;
+        quote! {
+            row.insert(#column.to_string(), serde_json::to_value(&self.#ident).unwrap_or(serde_json::Value::Null));
+        }
+    });
+
+    // 插入、更新取行上的更新时间作为源端时间，删除和无更新时间时取当前时间
+    let now_expr = config.now_expr();
+    let source_time = if config.timestamps {
+        let update_time = config.field("update_time");
+        let updated_at = config.timestamp_to_utc(quote! { time });
+        quote! {
+            after.filter(|_| !matches!(op, #change_op::Delete))
+                .and_then(|model| model.#update_time.and_then(|time| #updated_at))
+                .map(|time| time.naive_utc())
+                .unwrap_or(now)
+        }
+    } else {
+        quote! { now }
+    };
+    let row_version = if config.version {
+        let version = config.field("version");
+        quote! {
+            after.or(before)
+                .map(|model| serde_json::to_value(&model.#version).unwrap_or(serde_json::Value::Null))
+                .unwrap_or(serde_json::Value::Null)
+        }
+    } else {
+        quote! { serde_json::Value::Null }
+    };
+
+    Ok(quote! {
+        impl #struct_name {
+            /// Debezium 行镜像，键为数据库列名
+            fn auto_field_cdc_row(&self) -> serde_json::Value {
+                let mut row = serde_json::Map::new();
+                #(#columns)*
+                serde_json::Value::Object(row)
+            }
+
+            /// 构造 Debezium 格式的变更信封：before / after 为行镜像，op 为 c / u / d，
+            /// source.ts_ms 取更新时间，source.row_version 取版本号
+            pub fn to_cdc_envelope(before: Option<&Self>, after: Option<&Self>, op: #change_op) -> serde_json::Value {
+                let now = #now_expr;
+                let op_code = match op {
+                    #change_op::Insert => "c",
+                    #change_op::Update => "u",
+                    #change_op::Delete => "d",
+                };
+                serde_json::json!({
+                    "before": before.map(Self::auto_field_cdc_row),
+                    "after": after.map(Self::auto_field_cdc_row),
+                    "source": {
+                        "version": #connector_version,
+                        "connector": "auto_field",
+                        "ts_ms": (#source_time).and_utc().timestamp_millis(),
+                        "snapshot": "false",
+                        "table": #table_name,
+                        "row_version": #row_version,
+                    },
+                    "op": op_code,
+                    "ts_ms": now.and_utc().timestamp_millis(),
+                    "transaction": null,
+                })
+            }
+        }
+    })
+}
+
+/// 匿名化的数据主体列（数据库列名, Column 变体），默认为创建人，pii_subject 可指定其他列（如 user_id）
+fn pii_subject_column(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+) -> syn::Result<Option<(String, syn::Ident)>> {
+    match &config.pii_subject {
+        Some(pii_subject) => {
+            let field = fields.get(pii_subject).ok_or_else(|| syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("pii_subject is set but the struct has no `{}` field", pii_subject)
+            ))?;
+            Ok(Some((field.column.clone(), column_variant(&field.name, field.ident.span()))))
+        }
+        None if config.audit => Ok(Some(("create_id".to_string(), config.column("create_id")))),
+        None => Ok(None),
+    }
+}
+
+/// 生成个人信息列清单：Entity::PII_COLUMNS 常量，启用 registry 特性时同时登记到运行时库的个人信息注册表，
+/// 供数据地图等合规报表统一汇总
+fn generate_pii_inventory(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    input: &DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let columns: Vec<&str> = fields.fields.iter()
+        .filter(|field| field.config.pii)
+        .map(|field| field.column.as_str())
+        .collect();
+    if columns.is_empty() {
+        return Ok(quote! {});
+    }
+
+    let entity_name = config.entity_type();
+    let registry_entry = if cfg!(feature = "registry") {
+        let table_name = parse_table_name(&input.attrs).ok_or_else(|| syn::Error::new_spanned(
+            &input.ident,
+            "the `registry` feature requires #[sea_orm(table_name = \"...\")] on the entity"
+        ))?;
+        let subject = match pii_subject_column(config, fields)? {
+            Some((subject, _)) => quote! { Some(#subject) },
+            None => quote! { None },
+        };
+        quote! {
+            __auto_field_trait::registry::submit! {
+                __auto_field_trait::registry::PiiInventory {
+                    entity: module_path!(),
+                    table: #table_name,
+                    columns: &[#(#columns),*],
+                    subject: #subject,
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 标记为 #[auto_field(pii)] 的数据库列名
+            pub const PII_COLUMNS: &'static [&'static str] = &[#(#columns),*];
+        }
+
+        #registry_entry
+    })
+}
+
+/// 生成 GDPR 匿名化方法：清空或替换 #[auto_field(pii)] 列，保留记录本身。
+/// 经 ActiveModel::update 保存，before_save 照常刷新更新时间、更新人和版本号
+fn generate_anonymize_ext(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let pii_fields: Vec<&EntityField> = fields.fields.iter().filter(|field| field.config.pii).collect();
+    if pii_fields.is_empty() {
+        if let Some(pii_subject) = &config.pii_subject {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("pii_subject = \"{}\" requires at least one #[auto_field(pii)] field", pii_subject)
+            ));
+        }
+        return Ok(quote! {});
+    }
+    if fields.get("id").is_none() {
+        return Err(syn::Error::new_spanned(struct_name, "pii fields require an `id` field"));
+    }
+
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let managed: Vec<&syn::Ident> = config.managed_fields.values().collect();
+
+    let mut erasures = Vec::new();
+    for field in pii_fields {
+        let ident = &field.ident;
+        if managed.contains(&ident) {
+            return Err(syn::Error::new_spanned(ident, "pii cannot be used on a managed column"));
+        }
+        let value_ty = option_inner_type(&field.ty);
+        let value = match (&field.config.pii_replacement, value_ty) {
+            (Some(replacement), Some(value_ty)) => {
+                let value = str_value_tokens(replacement, value_ty)?;
+                quote! { Some(#value) }
+            }
+            (Some(replacement), None) => str_value_tokens(replacement, &field.ty)?,
+            // 可空列默认清空为 NULL
+            (None, Some(_)) => quote! { None },
+            (None, None) => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "pii on a non-nullable column requires a replacement value, e.g. #[auto_field(pii = \"deleted user\")]"
+                ));
+            }
+        };
+        erasures.push(quote! { active_model.#ident = sea_orm::ActiveValue::Set(#value); });
+    }
+
+    let subject = pii_subject_column(config, fields)?;
+    let by_subject = subject.map(|(subject, subject_column)| {
+        let log_call = config.ops_log_call(
+            "anonymize_by_subject",
+            quote! { anonymized },
+            quote! { format!("{{\"subject\":{}}}", Self::auto_field_json_string(subject_id)) },
+        );
+        let doc = doc_attrs(&format!(
+            "匿名化数据主体的全部记录（`{} = subject_id`，包括已软删除的记录），返回匿名化的条数",
+            subject
+        ));
+        quote! {
+            #doc
+            pub async fn anonymize_by_subject<C>(db: &C, subject_id: &str) -> Result<u64, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+                let models = Self::find().filter(#column_type::#subject_column.eq(subject_id)).all(db).await?;
+                let mut anonymized = 0;
+                for model in models {
+                    Self::auto_field_anonymize_model(db, model).await?;
+                    anonymized += 1;
+                }
+                #log_call
+                Ok(anonymized)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 匿名化单条记录：pii 列清空为 NULL 或写入配置的替换值，记录本身保留；
+            /// 记录不存在时返回 false，已软删除的记录同样匿名化
+            pub async fn anonymize<C>(db: &C, id: &str) -> Result<bool, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::EntityTrait;
+                let Some(model) = Self::find_by_id(id.to_owned()).one(db).await? else {
+                    return Ok(false);
+                };
+                Self::auto_field_anonymize_model(db, model).await?;
+                Ok(true)
+            }
+
+            #by_subject
+
+            async fn auto_field_anonymize_model<C>(db: &C, model: #struct_name) -> Result<(), sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::ActiveModelTrait;
+                let mut active_model: #active_model_name = model.into();
+                #(#erasures)*
+                active_model.update(db).await?;
+                Ok(())
+            }
+        }
+    })
+}
+
+/// 生成缓存的雪花 ID 生成器，首次成功获取后不再访问全局组件注册表
+fn generate_id_generator_cache(config: &AutoFieldConfig) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+
+    // 租户分片号：租户 ID 经 FNV-1a 哈希取 32 个值之一，追加在雪花 ID 末尾。
+    // ID 仍由全局组件生成，时间起点、datacenter 和 worker 位都不变，不同租户、不同进程的 ID 不会重复
+    let tenant_shard = if config.id_worker_from == IdWorkerSource::Tenant {
+        quote! {
+            /// 当前租户的 ID 分片号（0..32），追加在雪花 ID 末尾两位；上下文中没有租户时为 0
+            pub fn auto_field_id_shard() -> u32 {
+                let tenant_id = __auto_field_trait::AutoFieldContext::current_safe().tenant_id.unwrap_or_default();
+                if tenant_id.is_empty() {
+                    return 0;
+                }
+                let hash = tenant_id
+                    .bytes()
+                    .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+                (hash % 32) as u32
+            }
+
+            /// 从 ID 末尾两位读出租户分片号，供按 ID 路由到分片存储
+            pub fn id_shard(id: &str) -> Option<u32> {
+                id.get(id.len().checked_sub(2)?..)?.parse().ok()
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            #[doc(hidden)]
+            pub fn auto_field_id_generator() -> Option<&'static std::sync::Mutex<snowflake::SnowflakeIdGenerator>> {
+                static GENERATOR: std::sync::OnceLock<std::sync::Mutex<snowflake::SnowflakeIdGenerator>> = std::sync::OnceLock::new();
+                let generator = match GENERATOR.get() {
+                    Some(generator) => generator,
+                    None => {
+                        // 组件尚未注册时不缓存，下次调用继续尝试
+                        use spring::plugin::ComponentRegistry;
+                        let generator = spring::App::global().get_component::<snowflake::SnowflakeIdGenerator>()?;
+                        GENERATOR.get_or_init(|| std::sync::Mutex::new(generator))
+                    }
+                };
+                Some(generator)
+            }
+
+            #tenant_shard
+        }
+    })
+}
+
+/// 生成测试辅助方法，仅在使用方 crate 的测试构建中存在
+fn generate_test_support(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+
+    // Option 列要求 Set(Some(_))，非 Option 列只要求不是 NotSet
+    let filled = |target: &proc_macro2::TokenStream, column: &str| {
+        let field = fields.get(column)?;
+        let field_ident = &field.ident;
+        Some(if option_inner_type(&field.ty).is_some() {
+            quote! { matches!(&#target.#field_ident, sea_orm::ActiveValue::Set(Some(_)) | sea_orm::ActiveValue::Unchanged(Some(_))) }
+        } else {
+            quote! { !matches!(&#target.#field_ident, sea_orm::ActiveValue::NotSet) }
+        })
+    };
+    let fill_check = |target: &proc_macro2::TokenStream, column: &str| {
+        filled(target, column).map(|filled| {
+            quote! {
+                if !(#filled) {
+                    misses.push(#column);
+                }
+            }
+        })
+    };
+
+    let fill_checks: Vec<_> = config
+        .insert_filled_columns()
+        .into_iter()
+        .filter_map(|column| fill_check(&quote! { self }, column))
+        .collect();
+
+    // 自检从空的 ActiveModel 开始，哈希列依赖调用方赋值的来源列，不在检查范围内
+    let hash_column = config.hash.as_ref().map(|hash| hash.into.as_str());
+    let insert_checks: Vec<_> = config
+        .insert_filled_columns()
+        .into_iter()
+        .filter(|column| Some(*column) != hash_column)
+        .filter_map(|column| fill_check(&quote! { active_model }, column))
+        .collect();
+
+    // 更新时总是写入的列，版本号依赖读取值，不在检查范围内
+    let mut update_columns = Vec::new();
+    if config.fills_timestamps() {
+        update_columns.push("update_time");
+    }
+    if config.audit {
+        update_columns.extend(["update_by", "update_id"]);
+    }
+    let update_checks: Vec<_> = update_columns
+        .into_iter()
+        .filter_map(|column| fill_check(&quote! { active_model }, column))
+        .collect();
+
+    // 保护语义：调用方赋值后经过 before_save，插入时 overwrite 以外保留，更新时只有 protect 保留
+    let mut protected = Vec::new();
+    if config.fills_timestamps() {
+        let policy = config.policy(config.timestamps_policy);
+        protected.push(("create_time", true, policy != Some(FillPolicy::Overwrite)));
+        protected.push(("update_time", false, policy == Some(FillPolicy::Protect)));
+    }
+    if config.audit {
+        let policy = config.policy(config.audit_policy);
+        protected.push(("create_by", true, policy != Some(FillPolicy::Overwrite)));
+        protected.push(("update_by", false, policy == Some(FillPolicy::Protect)));
+    }
+    if config.tenant {
+        protected.push(("tenant_id", true, config.policy(config.tenant_policy) != Some(FillPolicy::Overwrite)));
+    }
+    let mut protection_checks = Vec::new();
+    for (column, insert, kept) in protected {
+        let Some(field) = fields.get(column) else {
+            continue;
+        };
+        let field_ident = &field.ident;
+        let sentinel = if column.ends_with("_time") {
+            config.timestamp_value(quote! { chrono::NaiveDate::from_ymd_opt(2000, 1, 1).and_then(|date| date.and_hms_opt(0, 0, 0)).unwrap() })
+        } else {
+            quote! { "auto_field_selftest_sentinel".into() }
+        };
+        let sentinel = if option_inner_type(&field.ty).is_some() {
+            quote! { Some(#sentinel) }
+        } else {
+            sentinel
+        };
+        let operation = if insert { "insert" } else { "update" };
+        let message = if kept {
+            format!("{} set by the caller should be kept on {}", column, operation)
+        } else {
+            format!("{} set by the caller should be overwritten on {}", column, operation)
+        };
+        protection_checks.push(quote! {
+            {
+                let mut active_model = <#active_model_name as Default>::default();
+                active_model.#field_ident = sea_orm::ActiveValue::Set(#sentinel);
+                let active_model = sea_orm::ActiveModelBehavior::before_save(active_model, db, #insert)
+                    .await
+                    .expect("before_save failed");
+                assert_eq!(active_model.#field_ident == sea_orm::ActiveValue::Set(#sentinel), #kept, #message);
+            }
+        });
+    }
+
+    // 软删除：软删除语句是 UPDATE 且写入 delete_flag，find_not_deleted 过滤 delete_flag
+    let soft_delete_check = if config.soft_delete {
+        let delete_flag_column = config.column("delete_flag");
+        quote! {
+            let delete_flag = sea_orm::IdenStatic::as_str(&#column_type::#delete_flag_column);
+            let statement = Self::soft_delete_sql(Self::auto_field_selftest_backend(), "auto_field_selftest").to_string();
+            assert!(statement.starts_with("UPDATE"), "soft delete should be an UPDATE: {}", statement);
+            assert!(statement.contains(delete_flag), "soft delete should set {}: {}", delete_flag, statement);
+            let query = sea_orm::QueryTrait::build(
+                &<Self as __auto_field_trait::QueryExtensions>::find_not_deleted(),
+                Self::auto_field_selftest_backend(),
+            )
+            .to_string();
+            assert!(query.contains(delete_flag), "find_not_deleted should filter {}: {}", delete_flag, query);
+        }
+    } else {
+        quote! {}
+    };
+
+    let selftest_backend = config.selftest_backend();
+
+    // 没有注册雪花 ID 生成器时 before_save 不填充 id，不算作遗漏
+    let id_unavailable = config.snowflake_id.then(|| {
+        quote! {
+            if Self::auto_field_id_generator().is_none() {
+                misses.retain(|column| *column != "id");
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[cfg(test)]
+        impl #entity_name {
+            /// 为当前测试安装 AutoFieldContext，守卫释放前生成的审计、租户填充都读取该上下文
+            pub fn test_context_guard(
+                context: __auto_field_trait::AutoFieldContext,
+            ) -> __auto_field_trait::AutoFieldContextGuard {
+                __auto_field_trait::AutoFieldContext::set_override(context)
+            }
+
+            #[doc(hidden)]
+            pub fn auto_field_selftest_backend() -> sea_orm::DbBackend {
+                #selftest_backend
+            }
+
+            #[doc(hidden)]
+            pub fn auto_field_selftest_context() -> __auto_field_trait::AutoFieldContext {
+                let mut context = __auto_field_trait::AutoFieldContext::default();
+                context.user_id = Some("auto_field_selftest_user_id".into());
+                context.user_name = Some("auto_field_selftest_user".into());
+                context.tenant_id = Some("auto_field_selftest_tenant_id".into());
+                context.tenant_name = Some("auto_field_selftest_tenant".into());
+                context
+            }
+
+            #[doc(hidden)]
+            pub async fn auto_field_selftest_insert_fill<C>(db: &C)
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let _guard = Self::test_context_guard(Self::auto_field_selftest_context());
+                let active_model = sea_orm::ActiveModelBehavior::before_save(<#active_model_name as Default>::default(), db, true)
+                    .await
+                    .expect("before_save failed on insert");
+                #[allow(unused_mut)]
+                let mut misses: Vec<&'static str> = Vec::new();
+                #(#insert_checks)*
+                #id_unavailable
+                assert!(misses.is_empty(), "insert did not fill {:?}", misses);
+            }
+
+            #[doc(hidden)]
+            pub async fn auto_field_selftest_update_fill<C>(db: &C)
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let _guard = Self::test_context_guard(Self::auto_field_selftest_context());
+                let active_model = sea_orm::ActiveModelBehavior::before_save(<#active_model_name as Default>::default(), db, false)
+                    .await
+                    .expect("before_save failed on update");
+                #[allow(unused_mut)]
+                let mut misses: Vec<&'static str> = Vec::new();
+                #(#update_checks)*
+                assert!(misses.is_empty(), "update did not fill {:?}", misses);
+            }
+
+            #[doc(hidden)]
+            #[allow(unused_variables)]
+            pub async fn auto_field_selftest_protection<C>(db: &C)
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let _guard = Self::test_context_guard(Self::auto_field_selftest_context());
+                #(#protection_checks)*
+            }
+
+            #[doc(hidden)]
+            pub fn auto_field_selftest_soft_delete() {
+                #soft_delete_check
+            }
+        }
+
+        #[cfg(test)]
+        impl #active_model_name {
+            /// 检查插入时应自动填充的受管列是否都已赋值，返回未填充的列名
+            pub fn assert_autofilled_for_insert(&self) -> Result<(), Vec<&'static str>> {
+                #[allow(unused_mut)]
+                let mut misses: Vec<&'static str> = Vec::new();
+                #(#fill_checks)*
+                if misses.is_empty() {
+                    Ok(())
+                } else {
+                    Err(misses)
+                }
+            }
+        }
+    })
+}
+
+/// 生成受管列元数据
+fn generate_entity_metadata(
+    config: &AutoFieldConfig,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+    let columns = config.managed_columns();
+    let snapshot_columns = config.snapshot_columns();
+
+    // 展开标记，cargo expand 输出中以文档注释形式出现
+    let expansion_marker = config.expansion_id.as_ref().map(|expansion_id| {
+        let doc = format!(" auto_field expansion_id: {}", expansion_id);
+        quote! {
+            #[doc = #doc]
+            #[doc(hidden)]
+            pub const AUTO_FIELD_EXPANSION_ID: &'static str = #expansion_id;
+        }
+    });
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 由 AutoField 宏自动维护的列，外部输入（导入、接口参数）不应写入这些列
+            pub const AUTO_FIELD_MANAGED: &'static [&'static str] = &[#(#columns),*];
+            /// 受管列中的插入时快照列（denormalize_snapshot），插入后不再改动
+            pub const AUTO_FIELD_SNAPSHOT: &'static [&'static str] = &[#(#snapshot_columns),*];
+            #expansion_marker
+        }
+    })
+}
+
+/// 字段值保护逻辑的辅助宏，字段未赋值（或 Option 字段为 None）时才填充
+fn should_fill_field_macro() -> proc_macro2::TokenStream {
+    quote! {
+        macro_rules! should_fill_field {
+            // 处理 Option<T> 类型字段
+            ($field:expr) => {
+                match &$field {
+                    sea_orm::ActiveValue::NotSet => true,
+                    sea_orm::ActiveValue::Set(None) => true,
+                    sea_orm::ActiveValue::Set(Some(_)) => false,
+                    sea_orm::ActiveValue::Unchanged(None) => true,
+                    sea_orm::ActiveValue::Unchanged(Some(_)) => false,
+                }
+            };
+            // 处理非 Option 类型字段
+            ($field:expr, $non_option:ty) => {
+                matches!(&$field, sea_orm::ActiveValue::NotSet)
+            };
+        }
+    }
+}
+
+/// 生成插入时受管列（时间戳、审计、租户、版本、删除标记、过期时间）的填充语句，
+/// 具体逻辑在运行时库 fill 模块中，这里只生成调用
+fn generate_managed_insert_fills(config: &AutoFieldConfig, target: &proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+    let not_deleted = config.not_deleted_value();
+    let create_time = config.field("create_time");
+    let update_time = config.field("update_time");
+    let create_by = config.field("create_by");
+    let create_id = config.field("create_id");
+    let tenant_id = config.field("tenant_id");
+    let tenant_name = config.field("tenant_name");
+    let version = config.field("version");
+    let delete_flag = config.field("delete_flag");
+    let expire_time = config.field("expire_time");
+    let fill = quote! { __auto_field_trait::fill };
+    let mut fills = Vec::new();
+
+    // 插入时默认保留调用方的值，overwrite 策略下总是覆盖
+    if config.fills_timestamps() {
+        let now_value = config.timestamp_value(quote! { now });
+        fills.push(match config.policy(config.timestamps_policy) {
+            Some(FillPolicy::Overwrite) => quote! {
+                #target.#create_time = sea_orm::ActiveValue::Set(Some(#now_value));
+                #target.#update_time = sea_orm::ActiveValue::Set(Some(#now_value));
+            },
+            // 调用方指定了 create_time 时，update_time 跟随创建时间
+            _ if config.init_update_fields_on_insert => {
+                let mirror = generate_insert_mirror(target, "create_time", "update_time", FillPolicy::Protect);
+                quote! {
+                    #fill::fill_unset(&mut #target.#create_time, #now_value);
+                    #mirror
+                    #fill::fill_unset(&mut #target.#update_time, #now_value);
+                }
+            }
+            _ => quote! {
+                #fill::fill_unset(&mut #target.#create_time, #now_value);
+                #fill::fill_unset(&mut #target.#update_time, #now_value);
+            },
+        });
+    }
+
+    // 创建人姓名和 ID 成对填充：上下文缺少其中之一时都不填，调用方填写了其中之一时都不覆盖
+    if config.audit {
+        let operator_present = operator_present();
+        let create_proxy_id = config.field("create_proxy_id");
+        fills.push(match config.policy(config.audit_policy) {
+            Some(FillPolicy::Overwrite) => {
+                let proxy = config.impersonation.then(|| quote! {
+                    #target.#create_proxy_id = sea_orm::ActiveValue::Set(context.acting_user_id.clone());
+                });
+                quote! {
+                    if #operator_present {
+                        #fill::set_non_empty(&mut #target.#create_by, &context.user_name);
+                        #fill::set_non_empty(&mut #target.#create_id, &context.user_id);
+                        #proxy
+                    }
+                }
+            }
+            _ => {
+                let proxy = config.impersonation.then(|| quote! {
+                    #fill::fill_non_empty(&mut #target.#create_proxy_id, &context.acting_user_id);
+                });
+                quote! {
+                    if #operator_present
+                        && #fill::is_unset(&#target.#create_by)
+                        && #fill::is_unset(&#target.#create_id)
+                    {
+                        #fill::fill_non_empty(&mut #target.#create_by, &context.user_name);
+                        #fill::fill_non_empty(&mut #target.#create_id, &context.user_id);
+                        #proxy
+                    }
+                }
+            }
+        });
+    }
+
+    // 更新人与创建人保持一致
+    if config.audit && config.init_update_fields_on_insert {
+        let policy = config.policy(config.audit_policy).unwrap_or(FillPolicy::Protect);
+        let update_by = generate_insert_mirror(target, "create_by", "update_by", policy);
+        let update_id = generate_insert_mirror(target, "create_id", "update_id", policy);
+        let update_proxy_id = config.impersonation
+            .then(|| generate_insert_mirror(target, "create_proxy_id", "update_proxy_id", policy));
+        fills.push(quote! {
+            #update_by
+            #update_id
+            #update_proxy_id
+        });
+    }
+
+    // 租户字段只在插入时填充，任何策略下更新都不会改动
+    if config.tenant {
+        fills.push(match config.policy(config.tenant_policy) {
+            Some(FillPolicy::Overwrite) => quote! {
+                #fill::set_non_empty(&mut #target.#tenant_id, &context.tenant_id);
+                #fill::set_non_empty(&mut #target.#tenant_name, &context.tenant_name);
+            },
+            _ => quote! {
+                if #fill::is_unset(&#target.#tenant_id) {
+                    #fill::fill_non_empty(&mut #target.#tenant_id, &context.tenant_id);
+                    #fill::fill_non_empty(&mut #target.#tenant_name, &context.tenant_name);
+                }
+            },
+        });
+    }
+
+    if config.version {
+        fills.push(quote! { #fill::fill_unset(&mut #target.#version, 1); });
+    }
+
+    if config.soft_delete {
+        fills.push(quote! { #fill::fill_unset(&mut #target.#delete_flag, #not_deleted); });
+    }
+
+    if let Some(ttl_seconds) = config.ttl_seconds {
+        fills.push(quote! {
+            #fill::fill_unset(&mut #target.#expire_time, now + chrono::Duration::seconds(#ttl_seconds));
+        });
+    }
+
+    fills
+}
+
+/// 上下文中操作人姓名和 ID 是否都不为空，审计列只在两者都存在时成对填充
+fn operator_present() -> proc_macro2::TokenStream {
+    quote! {
+        (context.user_name.as_deref().is_some_and(|user_name| !user_name.is_empty())
+            && context.user_id.as_deref().is_some_and(|user_id| !user_id.is_empty()))
+    }
+}
+
+/// 生成插入时用 source 列的值填充 target 列的语句
+fn generate_insert_mirror(
+    target: &proc_macro2::TokenStream,
+    source: &str,
+    destination: &str,
+    policy: FillPolicy,
+) -> proc_macro2::TokenStream {
+    let source = syn::Ident::new(source, proc_macro2::Span::call_site());
+    let destination = syn::Ident::new(destination, proc_macro2::Span::call_site());
+    let assign = match policy {
+        FillPolicy::Protect => quote! {
+            __auto_field_trait::fill::fill_unset(&mut #target.#destination, value);
+        },
+        FillPolicy::Overwrite => quote! {
+            #target.#destination = sea_orm::ActiveValue::Set(Some(value));
+        },
+    };
+    quote! {
+        if let sea_orm::ActiveValue::Set(Some(value)) | sea_orm::ActiveValue::Unchanged(Some(value)) = &#target.#source {
+            let value = value.clone();
+            #assign
+        }
+    }
+}
+
+/// 生成更新时受管列的填充语句
+fn generate_managed_update_fills(config: &AutoFieldConfig, target: &proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+    let update_time = config.field("update_time");
+    let update_by = config.field("update_by");
+    let update_id = config.field("update_id");
+    let version = config.field("version");
+    let fill = quote! { __auto_field_trait::fill };
+    let mut fills = Vec::new();
+
+    // 更新时默认覆盖，protect 策略下调用方本次已赋值（Set）的列保持不变
+    if config.fills_timestamps() {
+        let now_value = config.timestamp_value(quote! { now });
+        fills.push(match config.policy(config.timestamps_policy) {
+            Some(FillPolicy::Protect) => quote! {
+                if !matches!(&#target.#update_time, sea_orm::ActiveValue::Set(_)) {
+                    #target.#update_time = sea_orm::ActiveValue::Set(Some(#now_value));
+                }
+            },
+            _ => quote! {
+                #target.#update_time = sea_orm::ActiveValue::Set(Some(#now_value));
+            },
+        });
+    }
+
+    // 更新人姓名和 ID 成对写入，上下文缺少其中之一时都不写；实际操作人随更新人写入，未代操作时清空
+    if config.audit {
+        let operator_present = operator_present();
+        let update_proxy_id = config.field("update_proxy_id");
+        let proxy = config.impersonation.then(|| quote! {
+            #target.#update_proxy_id = sea_orm::ActiveValue::Set(context.acting_user_id.clone());
+        });
+        fills.push(match config.policy(config.audit_policy) {
+            Some(FillPolicy::Protect) => quote! {
+                if #operator_present
+                    && !matches!(&#target.#update_by, sea_orm::ActiveValue::Set(_))
+                    && !matches!(&#target.#update_id, sea_orm::ActiveValue::Set(_))
+                {
+                    #fill::set_non_empty(&mut #target.#update_by, &context.user_name);
+                    #fill::set_non_empty(&mut #target.#update_id, &context.user_id);
+                    #proxy
+                }
+            },
+            _ => quote! {
+                if #operator_present {
+                    #fill::set_non_empty(&mut #target.#update_by, &context.user_name);
+                    #fill::set_non_empty(&mut #target.#update_id, &context.user_id);
+                    #proxy
+                }
+            },
+        });
+    }
+
+    if config.version {
+        fills.push(quote! { #fill::bump_version(&mut #target.#version); });
+    }
+
+    fills
+}
+
+/// 生成 ActiveModelBehavior 实现
+fn generate_active_model_behavior(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    struct_name: &syn::Ident,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let entity_name = config.entity_type();
+    let id = config.field("id");
+    let create_by = config.field("create_by");
+    let update_by = config.field("update_by");
+    let mut before_insert_body = Vec::new();
+    let mut before_update_body = Vec::new();
+    // 插入和更新都需要执行的逻辑，加密字段放在最后，保证其他填充逻辑读取到的是明文
+    let before_save_tail = generate_encrypt_fills(config, fields, &quote! { self }, true)?;
+
+    let context_capture = config.context_capture();
+    // 每次 before_save 只取一次当前时间，保证同一次保存中各时间戳一致
+    let now_capture = if config.needs_now() {
+        let now_expr = config.now_expr();
+        quote! { let now = #now_expr; }
+    } else {
+        quote! {}
+    };
+
+    // 规范化和校验在所有填充和数据库查询之前执行，只处理调用方本次赋值的列，校验看到的是规范化后的值
+    let normalizations = generate_normalize_fills(fields, &quote! { self })?;
+    let validations = generate_validations(config, fields)?;
+
+    // 添加字段值保护逻辑的辅助宏
+    before_insert_body.push(should_fill_field_macro());
+
+    // 生成插入时的字段填充逻辑
+    if config.snowflake_id {
+        let assign_id = config.snowflake_id_assign(&quote! { generator }, &quote! { self });
+        before_insert_body.push(quote! {
+            if should_fill_field!(self.#id, String) {
+                if let Some(generator) = #entity_name::auto_field_id_generator() {
+                    let mut generator = generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    #assign_id
+                }
+            }
+        });
+    }
+
+    before_insert_body.extend(generate_managed_insert_fills(config, &quote! { self }));
+
+    // 字段级默认值，先于排序、slug、哈希等派生列执行
+    before_insert_body.extend(generate_field_default_fills(fields, &quote! { self })?);
+
+    // 派生列同步，在默认值之后执行，默认值填充的来源列同样会同步
+    before_insert_body.extend(generate_sync_fills(config, fields, &quote! { self })?);
+
+    // 排序列
+    before_insert_body.push(generate_sort_fill(config, fields, &quote! { self }, true)?);
+
+    // slug
+    before_insert_body.push(generate_slug_fill(config, fields, &quote! { self }, true)?);
+
+    // 拼音检索列
+    before_insert_body.extend(generate_pinyin_fills(config, fields, &quote! { self })?);
+
+    // 行哈希
+    before_insert_body.push(generate_hash_fill(config, fields, &quote! { self }, true)?);
+
+    // 租户配额在填充之后检查，使用最终写入的租户 ID
+    before_insert_body.push(generate_tenant_quota_check(config));
+
+    // 生成更新时的字段填充逻辑，先归档旧行
+    before_update_body.push(generate_archive_snapshot(config));
+    before_update_body.extend(generate_sync_fills(config, fields, &quote! { self })?);
+    before_update_body.extend(generate_pinyin_fills(config, fields, &quote! { self })?);
+    before_update_body.push(generate_hash_fill(config, fields, &quote! { self }, false)?);
+
+    before_update_body.extend(generate_managed_update_fills(config, &quote! { self }));
+
+    // 快照列插入后不再改动，丢弃调用方在更新时的赋值
+    for column in config.snapshot_columns() {
+        let ident = config.field(column);
+        before_update_body.push(quote! {
+            if matches!(&self.#ident, sea_orm::ActiveValue::Set(_)) {
+                self.#ident = sea_orm::ActiveValue::NotSet;
+            }
+        });
+    }
+
+    // 要求操作人时，未能填写操作人的保存直接失败
+    if config.audit_required {
+        let operator_required = auto_field_error(quote! {
+            OperatorRequired { table: sea_orm::EntityName::table_name(&#entity_name::default()).to_string() }
+        });
+        before_insert_body.push(quote! {
+            if __auto_field_trait::fill::is_unset(&self.#create_by) {
+                return Err(#operator_required);
+            }
+        });
+        before_update_body.push(quote! {
+            if !matches!(&self.#update_by, sea_orm::ActiveValue::Set(Some(_))) {
+                return Err(#operator_required);
+            }
+        });
+    }
+
+    let new_fn = generate_active_model_new(config, fields)?;
+    let after_save = generate_after_save(config, fields, struct_name);
+    let after_delete = generate_after_delete(config);
+    let before_save_doc = before_save_doc(config);
+
+    let before_save_body = quote! {
+        #(#normalizations)*
+        #(#validations)*
+        #context_capture
+        #now_capture
+
+        if insert {
+            #(#before_insert_body)*
+        } else {
+            #(#before_update_body)*
+        }
+        #(#before_save_tail)*
+        Ok(self)
+    };
+
+    // dyn_conn：主体放在只接收动态连接的固有方法中，trait 方法仅做一次转发
+    if config.dyn_conn {
+        let connection_name = quote::format_ident!("__AutoField{}Connection", struct_name);
+        return Ok(quote! {
+            use async_trait::async_trait;
+
+            /// 将任意连接包装为 &dyn ConnectionTrait，供 before_save 的非泛型主体使用
+            #[doc(hidden)]
+            pub struct #connection_name<'a>(&'a dyn sea_orm::ConnectionTrait);
+
+            #[async_trait::async_trait]
+            impl sea_orm::ConnectionTrait for #connection_name<'_> {
+                fn get_database_backend(&self) -> sea_orm::DbBackend {
+                    self.0.get_database_backend()
+                }
+
+                async fn execute(&self, stmt: sea_orm::Statement) -> Result<sea_orm::ExecResult, sea_orm::DbErr> {
+                    self.0.execute(stmt).await
+                }
+
+                async fn execute_unprepared(&self, sql: &str) -> Result<sea_orm::ExecResult, sea_orm::DbErr> {
+                    self.0.execute_unprepared(sql).await
+                }
+
+                async fn query_one(&self, stmt: sea_orm::Statement) -> Result<Option<sea_orm::QueryResult>, sea_orm::DbErr> {
+                    self.0.query_one(stmt).await
+                }
+
+                async fn query_all(&self, stmt: sea_orm::Statement) -> Result<Vec<sea_orm::QueryResult>, sea_orm::DbErr> {
+                    self.0.query_all(stmt).await
+                }
+
+                fn support_returning(&self) -> bool {
+                    self.0.support_returning()
+                }
+
+                fn is_mock_connection(&self) -> bool {
+                    self.0.is_mock_connection()
+                }
+            }
+
+            impl #active_model_name {
+                async fn auto_field_before_save(
+                    mut self,
+                    db: &#connection_name<'_>,
+                    insert: bool,
+                ) -> Result<Self, sea_orm::DbErr> {
+                    #before_save_body
+                }
+            }
+
+            #[async_trait::async_trait]
+            impl sea_orm::ActiveModelBehavior for #active_model_name {
+                #new_fn
+
+                #before_save_doc
+                async fn before_save<C>(self, db: &C, insert: bool) -> Result<Self, sea_orm::DbErr>
+                where
+                    C: sea_orm::ConnectionTrait,
+                {
+                    self.auto_field_before_save(&#connection_name(db), insert).await
+                }
+
+                #after_save
+                #after_delete
+            }
+        });
+    }
+
+    Ok(quote! {
+        use async_trait::async_trait;
+
+        #[async_trait::async_trait]
+        impl sea_orm::ActiveModelBehavior for #active_model_name {
+            #new_fn
+
+            // SeaORM 0.12 与 1.x 的 before_save 签名一致，sea-orm-0-12 / sea-orm-1 特性目前生成相同代码
+            #before_save_doc
+            async fn before_save<C>(mut self, db: &C, insert: bool) -> Result<Self, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                #before_save_body
+            }
+
+            #after_save
+            #after_delete
+        }
+    })
+}
+
+/// 生成字段级 trim / lowercase 规范化语句，只处理本次赋值（Set）的值
+fn generate_normalize_fills(
+    fields: &EntityFields,
+    target: &proc_macro2::TokenStream,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut fills = Vec::new();
+    for field in &fields.fields {
+        let FieldConfig { trim, lowercase, .. } = field.config;
+        if !trim && !lowercase {
+            continue;
+        }
+        let ident = &field.ident;
+        let is_option = option_inner_type(&field.ty).is_some();
+        if !is_string_like_type(option_inner_type(&field.ty).unwrap_or(&field.ty)) {
+            return Err(syn::Error::new_spanned(&field.ty, "trim and lowercase require a String or Option<String> field"));
+        }
+
+        let mut normalized = quote! { ::std::convert::AsRef::<str>::as_ref(&*value) };
+        if trim {
+            normalized = quote! { #normalized.trim() };
+        }
+        normalized = if lowercase {
+            quote! { #normalized.to_lowercase() }
+        } else {
+            quote! { #normalized.to_owned() }
+        };
+        let assign = quote! {
+            let normalized: ::std::string::String = #normalized;
+            *value = normalized.into();
+        };
+        fills.push(if is_option {
+            quote! {
+                if let sea_orm::ActiveValue::Set(Some(value)) = &mut #target.#ident {
+                    #assign
+                }
+            }
+        } else {
+            quote! {
+                if let sea_orm::ActiveValue::Set(value) = &mut #target.#ident {
+                    #assign
+                }
+            }
+        });
     }
+    Ok(fills)
 }
 
+/// 生成 before_save 中的校验语句：字段级 validate(...) 规则和实体级 validate_fn，
+/// 失败时返回 AutoFieldError::ValidationFailed
+fn generate_validations(config: &AutoFieldConfig, fields: &EntityFields) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let entity_name = config.entity_type();
+    let table = quote! { sea_orm::EntityName::table_name(&#entity_name::default()).to_string() };
+    let mut validations = Vec::new();
+    for field in &fields.fields {
+        let Some(rules) = &field.config.validate else {
+            continue;
+        };
+        let ident = &field.ident;
+        let column = &field.column;
+        let is_option = option_inner_type(&field.ty).is_some();
+        if !is_string_like_type(option_inner_type(&field.ty).unwrap_or(&field.ty)) {
+            return Err(syn::Error::new_spanned(&field.ty, "validate requires a String or Option<String> field"));
+        }
+        let failed = |message: proc_macro2::TokenStream| auto_field_error(quote! {
+            ValidationFailed { table: #table, column: Some(#column.to_string()), message: #message }
+        });
+
+        let mut checks = Vec::new();
+        if rules.non_empty {
+            let error = failed({
+                let message = config.message(Message::MustNotBeEmpty);
+                quote! { #message.to_string() }
+            });
+            checks.push(quote! {
+                if value.map_or(true, str::is_empty) {
+                    return Err(#error);
+                }
+            });
+        }
+        if let Some(min_len) = rules.min_len {
+            let message = config.message(Message::TooShort);
+            let error = failed(quote! { format!(#message, #min_len) });
+            checks.push(quote! {
+                if value.is_some_and(|value| value.chars().count() < #min_len) {
+                    return Err(#error);
+                }
+            });
+        }
+        if let Some(max_len) = rules.max_len {
+            let message = config.message(Message::TooLong);
+            let error = failed(quote! { format!(#message, #max_len) });
+            checks.push(quote! {
+                if value.is_some_and(|value| value.chars().count() > #max_len) {
+                    return Err(#error);
+                }
+            });
+        }
+        // 统一为 Option<&str>，None 只在 Option 列上出现
+        let value = if is_option {
+            quote! { value.as_ref().map(|value| ::std::convert::AsRef::<str>::as_ref(value)) }
+        } else {
+            quote! { Some(::std::convert::AsRef::<str>::as_ref(value)) }
+        };
+        validations.push(quote! {
+            if let sea_orm::ActiveValue::Set(value) = &self.#ident {
+                let value: Option<&str> = #value;
+                #(#checks)*
+            }
+        });
+    }
+
+    if let Some(validate_fn) = &config.validate_fn {
+        let error = auto_field_error(quote! {
+            ValidationFailed { table: #table, column: None, message: err.to_string() }
+        });
+        validations.push(quote! {
+            if let Err(err) = #validate_fn(&self, insert) {
+                return Err(#error);
+            }
+        });
+    }
+    Ok(validations)
+}
 
+/// 配置了 ops_log 时生成写入批量操作日志的辅助方法。日志行包含操作人、表名、操作名、影响条数、
+/// 条件 JSON 和时间，启用 tenant 时还包含租户 ID，通过执行批量操作的同一连接写入
+fn generate_ops_log_ext(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let Some(ops_log) = &config.ops_log else {
+        return quote! {};
+    };
+    let entity_name = config.entity_type();
+    let now_expr = config.now_expr();
+    let current_context = config.current_context();
+    // 多租户时按租户分区日志
+    let (tenant_column, tenant_value) = if config.tenant {
+        (
+            quote! { Alias::new("tenant_id"), },
+            quote! { sea_orm::Value::from(non_empty(context.tenant_id)).into(), },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
 
-/// AutoField 派生宏
-#[proc_macro_derive(AutoField, attributes(auto_field))]
-pub fn derive_auto_field(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
+    quote! {
+        impl #entity_name {
+            #[doc(hidden)]
+            pub async fn auto_field_log_operation<C>(
+                db: &C,
+                operation: &str,
+                affected: u64,
+                condition: String,
+            ) -> Result<(), sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::sea_query::{Alias, Query};
+                #current_context
+                let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+                let mut insert = Query::insert();
+                insert
+                    .into_table(Alias::new(#ops_log))
+                    .columns([
+                        Alias::new("operator_id"),
+                        Alias::new("operator_name"),
+                        #tenant_column
+                        Alias::new("entity"),
+                        Alias::new("operation"),
+                        Alias::new("affected"),
+                        Alias::new("condition"),
+                        Alias::new("created_at"),
+                    ])
+                    .values([
+                        sea_orm::Value::from(non_empty(context.user_id)).into(),
+                        sea_orm::Value::from(non_empty(context.user_name)).into(),
+                        #tenant_value
+                        sea_orm::Value::from(sea_orm::EntityName::table_name(&Self::default())).into(),
+                        sea_orm::Value::from(operation).into(),
+                        sea_orm::Value::from(affected as i64).into(),
+                        sea_orm::Value::from(condition).into(),
+                        sea_orm::Value::from(#now_expr).into(),
+                    ])
+                    .map_err(|err| sea_orm::DbErr::Custom(err.to_string()))?;
+                let statement = sea_orm::ConnectionTrait::get_database_backend(db).build(&insert);
+                sea_orm::ConnectionTrait::execute(db, statement).await?;
+                Ok(())
+            }
 
-    match generate_auto_field_impl(&input) {
-        Ok(tokens) => tokens.into(),
-        Err(err) => err.to_compile_error().into(),
+            #[doc(hidden)]
+            pub fn auto_field_ids_json(ids: &[String]) -> String {
+                let ids: Vec<String> = ids.iter().map(|id| Self::auto_field_json_string(id)).collect();
+                format!("{{\"ids\":[{}]}}", ids.join(","))
+            }
+
+            #[doc(hidden)]
+            pub fn auto_field_json_string(value: &str) -> String {
+                let mut json = String::with_capacity(value.len() + 2);
+                json.push('"');
+                for ch in value.chars() {
+                    match ch {
+                        '"' => json.push_str("\\\""),
+                        '\\' => json.push_str("\\\\"),
+                        '\n' => json.push_str("\\n"),
+                        '\r' => json.push_str("\\r"),
+                        '\t' => json.push_str("\\t"),
+                        ch if (ch as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", ch as u32)),
+                        ch => json.push(ch),
+                    }
+                }
+                json.push('"');
+                json
+            }
+        }
     }
 }
 
-/// 生成 AutoField 实现
-fn generate_auto_field_impl(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    // 解析配置
-    let config = AutoFieldConfig::from_attributes(&input.attrs)?;
-    config.validate()?;
+/// 配置了 archive_to 时生成归档语句：更新前按 id 读取旧行，连同归档时间和操作人写入历史表。
+/// 使用 before_save 收到的同一连接，在事务中保存时归档与更新一起提交或回滚
+fn generate_archive_snapshot(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let Some(archive_to) = &config.archive_to else {
+        return quote! {};
+    };
+    let entity_name = config.entity_type();
+    let column_type = config.column_type();
+    let id = config.field("id");
+    // 与本次保存的其他时间戳、操作人保持一致
+    let archived_at = if config.needs_now() { quote! { now } } else { config.now_expr() };
+    let archived_by = if config.needs_context() {
+        quote! { context.user_id.clone() }
+    } else {
+        quote! { __auto_field_trait::AutoFieldContext::current_safe().user_id }
+    };
 
-    let struct_name = &input.ident;
-    // SeaORM 生成的 ActiveModel 类型名称是 ActiveModel
-    let active_model_name = syn::Ident::new("ActiveModel", struct_name.span());
+    quote! {
+        if let Some(id) = self.#id.try_as_ref() {
+            let previous = <#entity_name as sea_orm::EntityTrait>::find_by_id(id.to_owned()).one(db).await?;
+            if let Some(previous) = previous {
+                let mut columns = Vec::new();
+                let mut values = Vec::new();
+                for column in <#column_type as sea_orm::Iterable>::iter() {
+                    columns.push(sea_orm::sea_query::Alias::new(sea_orm::IdenStatic::as_str(&column)));
+                    values.push(sea_orm::sea_query::SimpleExpr::from(sea_orm::ModelTrait::get(&previous, column)));
+                }
+                columns.push(sea_orm::sea_query::Alias::new("archived_at"));
+                values.push(sea_orm::sea_query::SimpleExpr::from(sea_orm::Value::from(#archived_at)));
+                columns.push(sea_orm::sea_query::Alias::new("archived_by"));
+                let archived_by = #archived_by.filter(|user_id| !user_id.is_empty());
+                values.push(sea_orm::sea_query::SimpleExpr::from(sea_orm::Value::from(archived_by)));
 
-    // 生成 ActiveModelBehavior 实现
-    let behavior_impl = generate_active_model_behavior(&config, &active_model_name)?;
+                let mut insert = sea_orm::sea_query::Query::insert();
+                insert
+                    .into_table(sea_orm::sea_query::Alias::new(#archive_to))
+                    .columns(columns)
+                    .values(values)
+                    .map_err(|err| sea_orm::DbErr::Custom(err.to_string()))?;
+                let statement = sea_orm::ConnectionTrait::get_database_backend(db).build(&insert);
+                sea_orm::ConnectionTrait::execute(db, statement).await?;
+            }
+        }
+    }
+}
 
-    // 生成 QueryExtensions 实现
-    let query_extensions_impl = generate_query_extensions(&config, struct_name)?;
+/// 配置了 tenant_quota_fn 时生成插入前的配额检查，租户 ID 为空时不检查，
+/// 检查函数返回 false 时以 AutoFieldError::QuotaExceeded 中止插入
+fn generate_tenant_quota_check(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let Some(tenant_quota_fn) = &config.tenant_quota_fn else {
+        return quote! {};
+    };
+    let entity_name = config.entity_type();
+    let tenant_id = config.field("tenant_id");
+    let table = quote! { sea_orm::EntityName::table_name(&#entity_name::default()) };
+    let quota_exceeded = auto_field_error(quote! {
+        QuotaExceeded { table: #table.to_string(), tenant_id: tenant_id.to_string() }
+    });
+    quote! {
+        if let sea_orm::ActiveValue::Set(Some(tenant_id)) | sea_orm::ActiveValue::Unchanged(Some(tenant_id)) = &self.#tenant_id {
+            let tenant_id: &str = ::std::convert::AsRef::<str>::as_ref(tenant_id);
+            if !tenant_id.is_empty() && !#tenant_quota_fn(db, #table, tenant_id).await? {
+                return Err(#quota_exceeded);
+            }
+        }
+    }
+}
 
-    // 生成 CustomizationExt 实现
-    let soft_delete_impl = generate_soft_delete_ext(&config, struct_name, &active_model_name)?;
+/// 配置了 search_sync_fn 时生成 after_save：保存后把文档推送到检索索引，已软删除的记录从索引中移除
+fn generate_after_save(config: &AutoFieldConfig, fields: &EntityFields, struct_name: &syn::Ident) -> proc_macro2::TokenStream {
+    let Some(search_sync_fn) = &config.search_sync_fn else {
+        return quote! {};
+    };
+    let entity_name = config.entity_type();
+    let id = config.field("id");
+    // 有脱敏列时推送脱敏后的副本，与接口对外的视图一致
+    let (masked, document) = if fields.fields.iter().any(|field| field.config.mask.is_some()) {
+        (quote! { let masked = model.masked(); }, quote! { &masked })
+    } else {
+        (quote! {}, quote! { &model })
+    };
+    let sync = if config.soft_delete {
+        let delete_flag = config.field("delete_flag");
+        let is_deleted = config.is_deleted_check(quote! { model.#delete_flag });
+        quote! {
+            #masked
+            let document: Option<&#struct_name> = if #is_deleted { None } else { Some(#document) };
+            #search_sync_fn(db, table, &id, document).await?;
+        }
+    } else {
+        quote! {
+            #masked
+            #search_sync_fn(db, table, &id, Some(#document)).await?;
+        }
+    };
+    let doc = doc_attrs(&format!(
+        "保存后调用 `{}` 同步检索索引，由 `#[auto_field(search_sync_fn = \"...\")]` 配置。\n\n已软删除的记录以 `None` 推送，表示从索引中移除",
+        quote!(#search_sync_fn).to_string().replace(' ', "")
+    ));
+    quote! {
+        #doc
+        async fn after_save<C>(model: #struct_name, db: &C, _insert: bool) -> Result<#struct_name, sea_orm::DbErr>
+        where
+            C: sea_orm::ConnectionTrait,
+        {
+            let entity = #entity_name::default();
+            let table = sea_orm::EntityName::table_name(&entity);
+            let id: String = model.#id.clone().into();
+            #sync
+            Ok(model)
+        }
+    }
+}
 
-    Ok(quote! {
-        #behavior_impl
-        #query_extensions_impl
-        #soft_delete_impl
-    })
+/// 配置了 after_delete_fn 或 search_sync_fn 时生成 after_delete，硬删除后调用使用方函数做缓存清理，并从检索索引中移除
+fn generate_after_delete(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    if config.after_delete_fn.is_none() && config.search_sync_fn.is_none() {
+        return quote! {};
+    }
+    let entity_name = config.entity_type();
+    let id = config.field("id");
+
+    let mut doc = String::from("硬删除后");
+    let mut calls = Vec::new();
+    if let Some(after_delete_fn) = &config.after_delete_fn {
+        doc.push_str(&format!(
+            "调用 `{}`，由 `#[auto_field(after_delete_fn = \"...\")]` 配置。",
+            quote!(#after_delete_fn).to_string().replace(' ', "")
+        ));
+        calls.push(quote! { #after_delete_fn(&self, db).await?; });
+    }
+    if let Some(search_sync_fn) = &config.search_sync_fn {
+        doc.push_str(&format!(
+            "以 `None` 调用 `{}` 从检索索引中移除，由 `#[auto_field(search_sync_fn = \"...\")]` 配置。",
+            quote!(#search_sync_fn).to_string().replace(' ', "")
+        ));
+        calls.push(quote! {
+            if let sea_orm::ActiveValue::Set(id) | sea_orm::ActiveValue::Unchanged(id) = &self.#id {
+                let entity = #entity_name::default();
+                let table = sea_orm::EntityName::table_name(&entity);
+                let id: String = id.clone().into();
+                #search_sync_fn(db, table, &id, None::<&<#entity_name as sea_orm::EntityTrait>::Model>).await?;
+            }
+        });
+    }
+    doc.push_str("\n\n只在 `ActiveModel::delete` 时触发，`Entity::delete_many`、`delete_by_id` 和软删除不会触发");
+    let doc = doc_attrs(&doc);
+    quote! {
+        #doc
+        async fn after_delete<C>(self, db: &C) -> Result<Self, sea_orm::DbErr>
+        where
+            C: sea_orm::ConnectionTrait,
+        {
+            #(#calls)*
+            Ok(self)
+        }
+    }
 }
 
-/// 生成 ActiveModelBehavior 实现
-fn generate_active_model_behavior(
+/// 生成 ActiveModelBehavior::new，SeaORM 的 ActiveModel::default() 会调用它。
+/// 只预置编译期可知的值（删除标记、版本号、排序、字段级默认值），时间、操作人等仍在 before_save 中填充
+fn generate_active_model_new(
     config: &AutoFieldConfig,
-    active_model_name: &syn::Ident,
+    fields: &EntityFields,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    let mut before_insert_body = Vec::new();
-    let mut before_update_body = Vec::new();
+    let not_deleted = config.not_deleted_value();
+    let version = config.field("version");
+    let delete_flag = config.field("delete_flag");
+    if !config.active_model_defaults {
+        return Ok(quote! {});
+    }
 
-    // 添加字段值保护逻辑的辅助宏
-    before_insert_body.push(quote! {
-        macro_rules! should_fill_field {
-            // 处理 Option<T> 类型字段
-            ($field:expr) => {
-                match &$field {
-                    sea_orm::ActiveValue::NotSet => true,
-                    sea_orm::ActiveValue::Set(None) => true,
-                    sea_orm::ActiveValue::Set(Some(_)) => false,
-                    sea_orm::ActiveValue::Unchanged(None) => true,
-                    sea_orm::ActiveValue::Unchanged(Some(_)) => false,
-                }
-            };
-            // 处理非 Option 类型字段
-            ($field:expr, $non_option:ty) => {
-                matches!(&$field, sea_orm::ActiveValue::NotSet)
+    let idents = fields.fields.iter().map(|field| &field.ident);
+    let fill = quote! { __auto_field_trait::fill };
+    let mut presets = vec![should_fill_field_macro()];
+    if config.version {
+        presets.push(quote! { #fill::fill_unset(&mut active_model.#version, 1); });
+    }
+    if config.soft_delete {
+        presets.push(quote! { #fill::fill_unset(&mut active_model.#delete_flag, #not_deleted); });
+    }
+    presets.extend(generate_field_default_fills(fields, &quote! { active_model })?);
+    // 追加模式需要查询数据库，只有 sort_mode = "zero" 会预置
+    presets.push(generate_sort_fill(config, fields, &quote! { active_model }, false)?);
+
+    Ok(quote! {
+        /// 预置删除标记、版本号、排序和字段级默认值（由 `#[auto_field(active_model_defaults)]` 启用），
+        /// `ActiveModel::default()` 会调用此方法
+        fn new() -> Self {
+            let mut active_model = Self {
+                #(#idents: sea_orm::ActiveValue::NotSet),*
             };
+            #(#presets)*
+            active_model
         }
-    });
+    })
+}
 
-    // 生成插入时的字段填充逻辑
+/// 生成文档注释属性，多行文本每行一个 #[doc]
+fn doc_attrs(doc: &str) -> proc_macro2::TokenStream {
+    let lines = doc.lines().map(|line| format!(" {}", line));
+    quote! { #(#[doc = #lines])* }
+}
+
+/// 查询方法的文档：过滤条件及启用它的配置，not_deleted 为未删除条件的描述
+fn query_method_doc(summary: &str, filter: Option<(&str, &str)>, not_deleted: Option<&str>) -> proc_macro2::TokenStream {
+    let mut doc = String::from(summary);
+    match filter {
+        Some((column, feature)) => doc.push_str(&format!("\n\n过滤条件：`{} = 参数`（由 `#[auto_field({})]` 启用）", column, feature)),
+        None => doc.push_str("\n\n未启用对应配置，忽略参数，等同于 `Entity::find()`"),
+    }
+    if let Some(not_deleted) = not_deleted {
+        doc.push_str(&format!("，并排除已软删除的记录（`{}`）", not_deleted));
+    }
+    doc_attrs(&doc)
+}
+
+/// before_save 的文档：插入、更新时填充的列及调用方已赋值时的处理方式
+fn before_save_doc(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let describe = |policy: Option<FillPolicy>| match policy {
+        Some(FillPolicy::Protect) => "调用方已赋值时插入、更新均保留",
+        Some(FillPolicy::Overwrite) => "插入、更新均覆盖调用方的值",
+        None => "插入时保留调用方的值，更新时覆盖",
+    };
+
+    let mut doc = String::from("由 `#[derive(AutoField)]` 生成，保存前自动填充受管字段。\n");
     if config.snowflake_id {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.id, String) {
-                use spring::plugin::ComponentRegistry;
+        doc.push_str("\n- `id`：插入时未赋值则生成雪花 ID（snowflake_id）");
+    }
+    if config.fills_timestamps() {
+        doc.push_str(&format!("\n- `create_time` / `update_time`：{}（timestamps）", describe(config.policy(config.timestamps_policy))));
+    }
+    if config.audit {
+        doc.push_str(&format!("\n- `create_by` / `create_id` / `update_by` / `update_id`：取自 AutoFieldContext，姓名和 ID 成对填充，{}（audit）", describe(config.policy(config.audit_policy))));
+    }
+    let snapshot_columns = config.snapshot_columns();
+    if !snapshot_columns.is_empty() {
+        doc.push_str(&format!("\n- `{}`：插入时的快照，更新时丢弃调用方的赋值（denormalize_snapshot）", snapshot_columns.join("` / `")));
+    }
+    if config.impersonation {
+        doc.push_str("\n- `create_proxy_id` / `update_proxy_id`：代操作时随创建人、更新人写入实际操作人 ID，否则为空（impersonation）");
+    }
+    if config.audit_required {
+        doc.push_str("\n- 上下文中没有操作人时返回 OperatorRequired 错误（audit_required）");
+    }
+    if let Some(validate_fn) = &config.validate_fn {
+        doc.push_str(&format!(
+            "\n- 填充前调用 `{}` 校验，失败时返回 ValidationFailed 错误（validate_fn）",
+            quote!(#validate_fn).to_string().replace(' ', "")
+        ));
+    }
+    if config.tenant {
+        doc.push_str(&format!("\n- `tenant_id` / `tenant_name`：取自 AutoFieldContext，{}（tenant）", describe(config.policy(config.tenant_policy))));
+    }
+    if let Some(tenant_quota_fn) = &config.tenant_quota_fn {
+        doc.push_str(&format!(
+            "\n- 插入前调用 `{}` 检查租户配额，超出时返回 QuotaExceeded 错误（tenant_quota_fn）",
+            quote!(#tenant_quota_fn).to_string().replace(' ', "")
+        ));
+    }
+    if config.version {
+        doc.push_str("\n- `version`：插入时为 1，每次更新加 1（version）");
+    }
+    if config.soft_delete {
+        doc.push_str(&format!("\n- `delete_flag`：插入时未赋值则为 {}（soft_delete）", config.not_deleted_value()));
+    }
+    if let Some(archive_to) = &config.archive_to {
+        doc.push_str(&format!("\n- 更新前把旧行连同 `archived_at` / `archived_by` 写入 `{}`（archive_to）", archive_to));
+    }
+    let derived = config.derived_columns();
+    if !derived.is_empty() {
+        doc.push_str(&format!("\n- 派生列 `{}`：未赋值或来源列变化时重新计算", derived.join("` / `")));
+    }
+    doc_attrs(&doc)
+}
 
-                if let Some(mut generator) = spring::App::global().get_component::<snowflake::SnowflakeIdGenerator>() {
-                    if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| generator.generate().to_string())) {
-                        self.id = sea_orm::ActiveValue::Set(id);
-                    }
+/// 生成 Entity::base_scope，所有生成的查询、分页方法都经由它附加公共过滤条件，
+/// 新增全局条件（如租户隔离）只需修改这里
+fn generate_base_scope(config: &AutoFieldConfig) -> proc_macro2::TokenStream {
+    let entity_name = config.entity_type();
+    let (deleted, body, doc) = if config.soft_delete {
+        let not_deleted_condition = config.not_deleted_condition();
+        let deleted_condition = config.deleted_condition();
+        (
+            quote! { deleted },
+            quote! {
+                if deleted {
+                    query.filter(#deleted_condition)
+                } else {
+                    query.filter(#not_deleted_condition)
                 }
+            },
+            format!("生成的查询方法共用的基础范围：`{}`", config.not_deleted_description()),
+        )
+    } else {
+        (
+            quote! { _deleted },
+            quote! { query },
+            "生成的查询方法共用的基础范围，当前没有公共过滤条件，原样返回查询".to_string(),
+        )
+    };
+    let doc = doc_attrs(&doc);
+
+    quote! {
+        impl #entity_name {
+            #doc
+            pub fn base_scope<Q>(query: Q) -> Q
+            where
+                Q: sea_orm::QueryFilter,
+            {
+                Self::auto_field_scope(query, false)
             }
-        });
+
+            /// deleted 为 true 时改为回收站范围（已软删除的记录），其余条件与 base_scope 相同
+            #[doc(hidden)]
+            pub fn auto_field_scope<Q>(query: Q, #deleted: bool) -> Q
+            where
+                Q: sea_orm::QueryFilter,
+            {
+                #body
+            }
+        }
     }
+}
 
-    if config.timestamps {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.create_time) {
-                self.create_time = sea_orm::ActiveValue::Set(Some(chrono::Utc::now().naive_utc()));
+/// 生成 QueryExtensions 实现
+fn generate_query_extensions(
+    config: &AutoFieldConfig,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let create_by_column = config.column("create_by");
+    let create_id_column = config.column("create_id");
+    let tenant_id_column = config.column("tenant_id");
+    let entity_name = config.entity_type();
+
+    let find_not_deleted_query = config.with_sql_comment("find_not_deleted", quote! { Self::find() });
+    let find_by_tenant_id_query = config.with_sql_comment("find_by_tenant_id", quote! { Self::find() });
+    let find_by_creator_id_query = config.with_sql_comment("find_by_creator_id", quote! { Self::find() });
+    let find_by_creator_name_query = config.with_sql_comment("find_by_creator_name", quote! { Self::find() });
+
+    let mut methods = Vec::new();
+
+    let not_deleted_description = config.not_deleted_description();
+    let not_deleted_doc = if config.soft_delete {
+        doc_attrs(&format!("查询未软删除的记录，过滤条件：`{}`（由 `#[auto_field(soft_delete)]` 启用）", not_deleted_description))
+    } else {
+        doc_attrs("未启用 soft_delete，等同于 `Entity::find()`，不附加任何过滤条件")
+    };
+    let tenant_doc = query_method_doc(
+        "按租户查询",
+        config.tenant.then_some(("tenant_id", "tenant")),
+        (config.tenant && config.soft_delete).then_some(not_deleted_description.as_str()),
+    );
+    let creator_id_doc = query_method_doc(
+        "按创建人 ID 查询",
+        config.audit.then_some(("create_id", "audit")),
+        (config.audit && config.soft_delete).then_some(not_deleted_description.as_str()),
+    );
+    let creator_name_doc = query_method_doc(
+        "按创建人名称查询",
+        config.audit.then_some(("create_by", "audit")),
+        (config.audit && config.soft_delete).then_some(not_deleted_description.as_str()),
+    );
+
+    // 所有查询方法都经由 base_scope 附加公共过滤条件
+    methods.push(quote! {
+        #not_deleted_doc
+        fn find_not_deleted() -> sea_orm::Select<Self> {
+            use sea_orm::EntityTrait;
+            Self::base_scope(#find_not_deleted_query)
+        }
+    });
+
+    // 租户相关查询方法
+    if config.tenant {
+        methods.push(quote! {
+            #tenant_doc
+            fn find_by_tenant_id(tenant_id: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                Self::base_scope(#find_by_tenant_id_query.filter(Self::Column::#tenant_id_column.eq(tenant_id)))
             }
-            if should_fill_field!(self.update_time) {
-                self.update_time = sea_orm::ActiveValue::Set(Some(chrono::Utc::now().naive_utc()));
+        });
+    } else {
+        methods.push(quote! {
+            #tenant_doc
+            fn find_by_tenant_id(_tenant_id: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                #find_by_tenant_id_query
             }
         });
     }
 
+    // 创建人相关查询方法
     if config.audit {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.create_by) {
-                if let Some(user_name) = &context.user_name {
-                    if !user_name.is_empty() {
-                        self.create_by = sea_orm::ActiveValue::Set(Some(user_name.clone()));
-                    }
-                }
-                if should_fill_field!(self.create_id) {
-                    if let Some(user_id) = &context.user_id {
-                        if !user_id.is_empty() {
-                            self.create_id = sea_orm::ActiveValue::Set(Some(user_id.clone()));
-                        }
-                    }
-                }
+        methods.push(quote! {
+            #creator_id_doc
+            fn find_by_creator_id(user_id: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                Self::base_scope(#find_by_creator_id_query.filter(Self::Column::#create_id_column.eq(user_id)))
+            }
+        });
+        methods.push(quote! {
+            #creator_name_doc
+            fn find_by_creator_name(user_name: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                Self::base_scope(#find_by_creator_name_query.filter(Self::Column::#create_by_column.eq(user_name)))
+            }
+        });
+    } else {
+        methods.push(quote! {
+            #creator_id_doc
+            fn find_by_creator_id(_user_id: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                #find_by_creator_id_query
             }
         });
+        methods.push(quote! {
+            #creator_name_doc
+            fn find_by_creator_name(_user_name: &str) -> sea_orm::Select<Self> {
+                use sea_orm::EntityTrait;
+                #find_by_creator_name_query
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl __auto_field_trait::QueryExtensions for #entity_name {
+            #(#methods)*
+        }
+    })
+}
+
+/// 配置了 replica_reads 时，为 QueryExtensions 的查询生成 `_on` 变体，直接在传入的连接上执行，
+/// 读请求可以指向只读副本，保存仍在主库连接上经过 ActiveModelBehavior
+fn generate_replica_reads(config: &AutoFieldConfig, struct_name: &syn::Ident) -> proc_macro2::TokenStream {
+    if !config.replica_reads {
+        return quote! {};
     }
+    let entity_name = config.entity_type();
 
+    let mut methods = vec![quote! {
+        /// 在指定连接（如只读副本）上执行 find_not_deleted
+        pub async fn find_not_deleted_on<C>(db: &C) -> Result<Vec<#struct_name>, sea_orm::DbErr>
+        where
+            C: sea_orm::ConnectionTrait,
+        {
+            use __auto_field_trait::QueryExtensions;
+            Self::find_not_deleted().all(db).await
+        }
+    }];
     if config.tenant {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.tenant_id) {
-                if let Some(tenant_id) = &context.tenant_id {
-                    if !tenant_id.is_empty() {
-                        self.tenant_id = sea_orm::ActiveValue::Set(Some(tenant_id.clone()));
-                    }
-                }
-                if should_fill_field!(self.tenant_name) {
-                    if let Some(tenant_name) = &context.tenant_name {
-                        if !tenant_name.is_empty() {
-                            self.tenant_name = sea_orm::ActiveValue::Set(Some(tenant_name.clone()));
-                        }
-                    }
-                }
+        methods.push(quote! {
+            /// 在指定连接（如只读副本）上执行 find_by_tenant_id
+            pub async fn find_by_tenant_id_on<C>(db: &C, tenant_id: &str) -> Result<Vec<#struct_name>, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use __auto_field_trait::QueryExtensions;
+                Self::find_by_tenant_id(tenant_id).all(db).await
             }
         });
     }
+    if config.audit {
+        methods.push(quote! {
+            /// 在指定连接（如只读副本）上执行 find_by_creator_id
+            pub async fn find_by_creator_id_on<C>(db: &C, user_id: &str) -> Result<Vec<#struct_name>, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use __auto_field_trait::QueryExtensions;
+                Self::find_by_creator_id(user_id).all(db).await
+            }
 
-    if config.version {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.version) {
-                self.version = sea_orm::ActiveValue::Set(Some(1));
+            /// 在指定连接（如只读副本）上执行 find_by_creator_name
+            pub async fn find_by_creator_name_on<C>(db: &C, user_name: &str) -> Result<Vec<#struct_name>, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use __auto_field_trait::QueryExtensions;
+                Self::find_by_creator_name(user_name).all(db).await
             }
         });
     }
 
-    if config.soft_delete {
-        before_insert_body.push(quote! {
-            if should_fill_field!(self.delete_flag) {
-                self.delete_flag = sea_orm::ActiveValue::Set(Some(0));
-            }
-        });
+    quote! {
+        impl #entity_name {
+            #(#methods)*
+        }
     }
+}
 
-    // 生成更新时的字段填充逻辑
-    if config.timestamps {
-        before_update_body.push(quote! {
-            self.update_time = sea_orm::ActiveValue::Set(Some(chrono::Utc::now().naive_utc()));
-        });
+/// 同时启用 soft_delete 和 version 时，生成带期望版本号的软删除，UPDATE 以版本号为条件，
+/// 版本号不一致时返回 AutoFieldError::VersionConflict
+fn generate_versioned_soft_delete(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    if !(config.soft_delete && config.version) {
+        return Ok(quote! {});
     }
+    let deleted = config.deleted_value();
+    let delete_flag = config.field("delete_flag");
+    let version = config.field("version");
+    let version_column = config.column("version");
+    let not_deleted_condition = config.not_deleted_condition();
+    let is_deleted = config.is_deleted_check(quote! { model.#delete_flag });
+    let version_field = fields.get("version").ok_or_else(|| syn::Error::new(
+        proc_macro2::Span::call_site(),
+        "version is enabled but the struct has no `version` field"
+    ))?;
+    let version_ty = option_inner_type(&version_field.ty).unwrap_or(&version_field.ty);
+    let delete_state = delete_state_values(config, fields, false)?
+        .into_iter()
+        .map(|(ident, _, value)| quote! { active_model.#ident = sea_orm::ActiveValue::Set(#value); });
+    let version_conflict = auto_field_error(quote! {
+        VersionConflict {
+            table: sea_orm::EntityName::table_name(&Self::default()).to_string(),
+            expected: version as i64,
+            actual: actual.map(|actual| actual as i64),
+        }
+    });
 
-    if config.audit {
-        before_update_body.push(quote! {
-            if let Some(user_name) = &context.user_name {
-                if !user_name.is_empty() {
-                    self.update_by = sea_orm::ActiveValue::Set(Some(user_name.clone()));
-                }
+    Ok(quote! {
+        /// 按期望版本号软删除，UPDATE 附加 `version = 期望值` 条件，
+        /// 记录在读取后被他人修改时返回 `AutoFieldError::VersionConflict`，与其他丢失更新一样可被检测
+        pub async fn soft_delete_with_version<C>(
+            db: &C,
+            id: &str,
+            version: #version_ty,
+        ) -> Result<__auto_field_trait::SoftDeleteStatus, sea_orm::DbErr>
+        where
+            C: sea_orm::ConnectionTrait,
+        {
+            use sea_orm::{ActiveModelBehavior, ColumnTrait, EntityTrait, QueryFilter};
+            let Some(model) = Self::find_by_id(id.to_owned()).one(db).await? else {
+                return Ok(__auto_field_trait::SoftDeleteStatus::NotFound);
+            };
+            if #is_deleted {
+                return Ok(__auto_field_trait::SoftDeleteStatus::AlreadyDeleted);
             }
-            if let Some(user_id) = &context.user_id {
-                if !user_id.is_empty() {
-                    self.update_id = sea_orm::ActiveValue::Set(Some(user_id.clone()));
-                }
+            if model.#version != Some(version) {
+                let actual = model.#version;
+                return Err(#version_conflict);
             }
-        });
-    }
 
-    if config.version {
-        before_update_body.push(quote! {
-            match &self.version {
-                sea_orm::ActiveValue::Set(Some(current_version)) => {
-                    self.version = sea_orm::ActiveValue::Set(Some(current_version + 1));
-                }
-                sea_orm::ActiveValue::Set(None) => {
-                    self.version = sea_orm::ActiveValue::Set(Some(1));
-                }
-                sea_orm::ActiveValue::Unchanged(Some(current_version)) => {
-                    self.version = sea_orm::ActiveValue::Set(Some(current_version + 1));
+            let mut active_model: #active_model_name = model.into();
+            active_model.#delete_flag = sea_orm::ActiveValue::Set(Some(#deleted));
+            #(#delete_state)*
+            let active_model = <#active_model_name as ActiveModelBehavior>::before_save(active_model, db, false).await?;
+            // 读取之后被并发修改或删除时，条件不成立，UPDATE 不会影响任何行
+            let updated = Self::update(active_model)
+                .filter(#column_type::#version_column.eq(version))
+                .filter(#not_deleted_condition)
+                .exec(db)
+                .await;
+            match updated {
+                Ok(model) => {
+                    <#active_model_name as ActiveModelBehavior>::after_save(model, db, false).await?;
+                    Ok(__auto_field_trait::SoftDeleteStatus::Deleted)
                 }
-                sea_orm::ActiveValue::Unchanged(None) => {
-                    self.version = sea_orm::ActiveValue::Set(Some(1));
-                }
-                sea_orm::ActiveValue::NotSet => {
-                    self.version = sea_orm::ActiveValue::Set(Some(1));
+                Err(sea_orm::DbErr::RecordNotUpdated) => {
+                    let actual = Self::find_by_id(id.to_owned()).one(db).await?.and_then(|model| model.#version);
+                    Err(#version_conflict)
                 }
+                Err(err) => Err(err),
             }
-        });
+        }
+    })
+}
+
+/// 返回软删除（restore 为 false）或恢复时要写入的状态列：(字段, Column 变体, 取值)，
+/// 包括 delete_time 和 delete_sets_state 配置的状态列
+fn delete_state_values(
+    config: &AutoFieldConfig,
+    fields: &EntityFields,
+    restore: bool,
+) -> syn::Result<Vec<(syn::Ident, syn::Ident, proc_macro2::TokenStream)>> {
+    let mut values = Vec::new();
+    if config.delete_time {
+        let value = if restore {
+            quote! { None::<chrono::NaiveDateTime> }
+        } else {
+            let now = config.now_expr();
+            quote! { Some(#now) }
+        };
+        values.push((config.field("delete_time"), config.column("delete_time"), value));
+    }
+
+    let Some(delete_state) = &config.delete_state else {
+        return Ok(values);
+    };
+    let columns = if restore {
+        [("state", &delete_state.restore_state), ("state_name", &delete_state.restore_state_name)]
+    } else {
+        [("state", &Some(delete_state.state.clone())), ("state_name", &delete_state.state_name)]
+    };
+
+    for (column, value) in columns {
+        let Some(value) = value else {
+            continue;
+        };
+        let field = fields.get(column).ok_or_else(|| syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("delete_sets_state is enabled but the struct has no `{}` field", column)
+        ))?;
+        let ty = &field.ty;
+        let lit_str = syn::LitStr::new(value, field.ident.span());
+        let value = str_value_tokens(&lit_str, option_inner_type(ty).unwrap_or(ty))?;
+        let value = if option_inner_type(ty).is_some() {
+            quote! { Some(#value) }
+        } else {
+            value
+        };
+        values.push((field.ident.clone(), column_variant(column, field.ident.span()), value));
     }
+    Ok(values)
+}
+
+/// 生成软删除和恢复的 `_sql` 变体，返回与逐条软删除、恢复等效的 UPDATE 语句而不执行，供变更审批展示。
+/// 逐条执行时版本号写入读取值加 1，这里写作 version = version + 1，其余列与实际执行一致
+fn generate_soft_delete_sql(config: &AutoFieldConfig, fields: &EntityFields) -> syn::Result<proc_macro2::TokenStream> {
+    let column_type = config.column_type();
+    let id_column = config.column("id");
+    let delete_flag_column = config.column("delete_flag");
+    let deleted = config.deleted_value();
+    let not_deleted = config.not_deleted_value();
+    let not_deleted_condition = config.not_deleted_condition();
+    let deleted_condition = config.deleted_condition();
+    let delete_state = delete_state_values(config, fields, false)?
+        .into_iter()
+        .map(|(_, variant, value)| quote! { .col_expr(#column_type::#variant, sea_orm::sea_query::Expr::value(#value)) });
+    let restore_state = delete_state_values(config, fields, true)?
+        .into_iter()
+        .map(|(_, variant, value)| quote! { .col_expr(#column_type::#variant, sea_orm::sea_query::Expr::value(#value)) });
 
     Ok(quote! {
-        use async_trait::async_trait;
+        /// 返回软删除单条记录的等效语句，不执行
+        pub fn soft_delete_sql(backend: sea_orm::DbBackend, id: &str) -> sea_orm::Statement {
+            use sea_orm::{ColumnTrait, QueryFilter};
+            let update_many = <Self as __auto_field_trait::CustomizationExt>::batch_update()
+                .col_expr(#column_type::#delete_flag_column, sea_orm::sea_query::Expr::value(Some(#deleted)))
+                #(#delete_state)*
+                .filter(#column_type::#id_column.eq(id))
+                .filter(#not_deleted_condition);
+            sea_orm::QueryTrait::build(&update_many, backend)
+        }
 
-        #[async_trait::async_trait]
-        impl sea_orm::ActiveModelBehavior for #active_model_name {
-            async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, sea_orm::DbErr>
-            where
-                C: sea_orm::ConnectionTrait,
-            {
-                let context = ::auto_field_trait::auto_field_trait::AutoFieldContext::current_safe();
+        /// 返回批量软删除逐条执行的等效语句，不执行
+        pub fn soft_delete_many_sql(backend: sea_orm::DbBackend, ids: &[String]) -> Vec<sea_orm::Statement> {
+            ids.iter().map(|id| Self::soft_delete_sql(backend, id)).collect()
+        }
 
-                if insert {
-                    #(#before_insert_body)*
-                } else {
-                    #(#before_update_body)*
-                }
-                Ok(self)
-            }
+        /// 返回从回收站恢复单条记录的等效语句，不执行
+        pub fn restore_sql(backend: sea_orm::DbBackend, id: &str) -> sea_orm::Statement {
+            use sea_orm::{ColumnTrait, QueryFilter};
+            let update_many = <Self as __auto_field_trait::CustomizationExt>::batch_update()
+                .col_expr(#column_type::#delete_flag_column, sea_orm::sea_query::Expr::value(Some(#not_deleted)))
+                #(#restore_state)*
+                .filter(#column_type::#id_column.eq(id))
+                .filter(#deleted_condition);
+            sea_orm::QueryTrait::build(&update_many, backend)
+        }
+
+        /// 返回批量恢复逐条执行的等效语句，不执行
+        pub fn restore_many_sql(backend: sea_orm::DbBackend, ids: &[String]) -> Vec<sea_orm::Statement> {
+            ids.iter().map(|id| Self::restore_sql(backend, id)).collect()
         }
     })
 }
 
-/// 生成 QueryExtensions 实现
-fn generate_query_extensions(
+/// 生成回收站查询和恢复方法
+fn generate_recycle_bin_ext(
     config: &AutoFieldConfig,
+    fields: &EntityFields,
     struct_name: &syn::Ident,
+    active_model_name: &proc_macro2::TokenStream,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    // SeaORM 生成的 Entity 类型名称是 Entity
-    let entity_name = syn::Ident::new("Entity", struct_name.span());
+    let column_type = config.column_type();
+    let not_deleted = config.not_deleted_value();
+    let update_time = config.field("update_time");
+    let update_by = config.field("update_by");
+    let delete_flag = config.field("delete_flag");
+    let update_time_column = config.column("update_time");
+    if !config.soft_delete {
+        return Ok(quote! {});
+    }
 
-    let mut methods = Vec::new();
+    let entity_name = config.entity_type();
 
-    // find_not_deleted 方法 - 总是添加 delete_flag = 0 条件
-    if config.soft_delete {
-        methods.push(quote! {
-            fn find_not_deleted() -> sea_orm::Select<Self> {
-                use sea_orm::EntityTrait;
-                Self::find().filter(Self::Column::DeleteFlag.eq(0))
-            }
-        });
+    // 删除时间优先取 delete_time；否则软删除会刷新 update_time / update_by，即删除时间和删除人
+    let (order, deleted_at) = if config.delete_time {
+        let delete_time = config.field("delete_time");
+        let delete_time_column = config.column("delete_time");
+        (quote! { .order_by_desc(#column_type::#delete_time_column) }, quote! { model.#delete_time })
+    } else if config.timestamps {
+        let updated_at = config.timestamp_to_utc(quote! { time });
+        (
+            quote! { .order_by_desc(#column_type::#update_time_column) },
+            quote! { model.#update_time.and_then(|time| #updated_at).map(|time| time.naive_utc()) },
+        )
     } else {
-        methods.push(quote! {
-            fn find_not_deleted() -> sea_orm::Select<Self> {
-                use sea_orm::EntityTrait;
-                Self::find()
-            }
-        });
-    }
-
-    // 租户相关查询方法
-    if config.tenant {
-        if config.soft_delete {
-            methods.push(quote! {
-                fn find_by_tenant_id(tenant_id: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find()
-                        .filter(Self::Column::TenantId.eq(tenant_id))
-                        .filter(Self::Column::DeleteFlag.eq(0))
-                }
-            });
-        } else {
-            methods.push(quote! {
-                fn find_by_tenant_id(tenant_id: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find().filter(Self::Column::TenantId.eq(tenant_id))
-                }
-            });
-        }
+        (quote! {}, quote! { None })
+    };
+    let deleted_by = if config.audit {
+        quote! { model.#update_by.clone().map(::std::convert::Into::into) }
     } else {
-        methods.push(quote! {
-            fn find_by_tenant_id(_tenant_id: &str) -> sea_orm::Select<Self> {
-                use sea_orm::EntityTrait;
-                Self::find()
-            }
-        });
-    }
+        quote! { None }
+    };
+    // 配置了 restore_state 时，恢复同时写回状态
+    let restore_state = delete_state_values(config, fields, true)?
+        .into_iter()
+        .map(|(ident, _, value)| quote! { active_model.#ident = sea_orm::ActiveValue::Set(#value); });
 
-    // 创建人相关查询方法
-    if config.audit {
-        // find_by_creator_id 方法
-        if config.soft_delete {
-            methods.push(quote! {
-                fn find_by_creator_id(user_id: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find()
-                        .filter(Self::Column::CreateId.eq(user_id))
-                        .filter(Self::Column::DeleteFlag.eq(0))
-                }
-            });
-        } else {
-            methods.push(quote! {
-                fn find_by_creator_id(user_id: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find().filter(Self::Column::CreateId.eq(user_id))
-                }
-            });
-        }
+    let find_recycle_bin_query = config.with_sql_comment("find_recycle_bin", quote! { Self::find() });
 
-        // find_by_creator_name 方法
-        if config.soft_delete {
-            methods.push(quote! {
-                fn find_by_creator_name(user_name: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find()
-                        .filter(Self::Column::CreateBy.eq(user_name))
-                        .filter(Self::Column::DeleteFlag.eq(0))
-                }
-            });
-        } else {
-            methods.push(quote! {
-                fn find_by_creator_name(user_name: &str) -> sea_orm::Select<Self> {
-                    use sea_orm::EntityTrait;
-                    Self::find().filter(Self::Column::CreateBy.eq(user_name))
-                }
-            });
-        }
-    } else {
-        methods.push(quote! {
-            fn find_by_creator_id(_user_id: &str) -> sea_orm::Select<Self> {
-                use sea_orm::EntityTrait;
-                Self::find()
+    let restore_many_log = config.ops_log_call("restore_many", quote! { restored }, quote! { Self::auto_field_ids_json(ids) });
+    // 恢复成功后调用 on_restore，返回错误时 restore 返回该错误，记录保持已恢复
+    let (restore_doc, on_restore) = match &config.on_restore {
+        Some(on_restore) => (
+            doc_attrs(&format!(
+                "从回收站恢复记录，记录不存在或未删除时返回 false\n\n恢复成功后调用 `{}`，由 `#[auto_field(on_restore = \"...\")]` 配置",
+                quote!(#on_restore).to_string().replace(' ', "")
+            )),
+            quote! { #on_restore(db, &model).await?; },
+        ),
+        None => (doc_attrs("从回收站恢复记录，记录不存在或未删除时返回 false"), quote! {}),
+    };
+
+    Ok(quote! {
+        impl #entity_name {
+            /// 回收站：分页查询已删除的记录（page 从 0 开始），按删除时间倒序
+            pub async fn find_recycle_bin<C>(
+                db: &C,
+                page: u64,
+                size: u64,
+            ) -> Result<__auto_field_trait::RecycleBinPage<#struct_name>, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::{EntityTrait, PaginatorTrait, QueryOrder};
+                let paginator = Self::auto_field_scope(#find_recycle_bin_query, true)
+                    #order
+                    .paginate(db, size);
+                let total = paginator.num_items().await?;
+                let items = paginator
+                    .fetch_page(page)
+                    .await?
+                    .into_iter()
+                    .map(|model| __auto_field_trait::RecycleBinEntry {
+                        deleted_at: #deleted_at,
+                        deleted_by: #deleted_by,
+                        model,
+                    })
+                    .collect();
+                Ok(__auto_field_trait::RecycleBinPage { items, total, page, size })
             }
-        });
-        methods.push(quote! {
-            fn find_by_creator_name(_user_name: &str) -> sea_orm::Select<Self> {
-                use sea_orm::EntityTrait;
-                Self::find()
+
+            #restore_doc
+            pub async fn restore<C>(db: &C, id: &str) -> Result<bool, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::{ActiveModelTrait, EntityTrait};
+                let Some(model) = Self::auto_field_scope(Self::find_by_id(id.to_owned()), true).one(db).await? else {
+                    return Ok(false);
+                };
+                let mut active_model: #active_model_name = model.into();
+                // 恢复同样经过 before_save，刷新更新时间、更新人和版本号
+                active_model.#delete_flag = sea_orm::ActiveValue::Set(Some(#not_deleted));
+                #(#restore_state)*
+                #[allow(unused_variables)]
+                let model = active_model.update(db).await?;
+                #on_restore
+                Ok(true)
             }
-        });
-    }
 
-    Ok(quote! {
-        impl ::auto_field_trait::auto_field_trait::QueryExtensions for #entity_name {
-            #(#methods)*
+            /// 批量从回收站恢复记录，返回实际恢复的条数
+            pub async fn restore_many<C>(db: &C, ids: &[String]) -> Result<u64, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                let mut restored = 0;
+                for id in ids {
+                    if Self::restore(db, id).await? {
+                        restored += 1;
+                    }
+                }
+                #restore_many_log
+                Ok(restored)
+            }
         }
     })
 }
@@ -475,140 +7713,104 @@ fn generate_query_extensions(
 /// 生成 CustomizationExt 实现
 fn generate_soft_delete_ext(
     config: &AutoFieldConfig,
-    struct_name: &syn::Ident,
-    active_model_name: &syn::Ident,
+    fields: &EntityFields,
+    active_model_name: &proc_macro2::TokenStream,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    // SeaORM 生成的 Entity 类型名称是 Entity
-    let entity_name = syn::Ident::new("Entity", struct_name.span());
+    let deleted = config.deleted_value();
+    let id = config.field("id");
+    let delete_flag = config.field("delete_flag");
+    let update_time_column = config.column("update_time");
+    let update_by_column = config.column("update_by");
+    let update_id_column = config.column("update_id");
+    let version_column = config.column("version");
+    let is_deleted = config.is_deleted_check(quote! { model.#delete_flag });
+    let entity_name = config.entity_type();
 
     // 生成自动字段填充逻辑
     let mut before_insert_body = Vec::new();
+    let now = config.now_expr();
+    let context_capture = config.context_capture();
+    // 批量插入只取一次当前时间，同一批次的时间戳保持一致
+    let now_capture = if config.needs_now() {
+        quote! { let now = #now; }
+    } else {
+        quote! {}
+    };
     // 添加字段值保护逻辑的辅助宏
-    before_insert_body.push(quote! {
-        macro_rules! should_fill_field {
-            // 处理 Option<T> 类型字段
-            ($field:expr) => {
-                match &$field {
-                    sea_orm::ActiveValue::NotSet => true,
-                    sea_orm::ActiveValue::Set(None) => true,
-                    sea_orm::ActiveValue::Set(Some(_)) => false,
-                    sea_orm::ActiveValue::Unchanged(None) => true,
-                    sea_orm::ActiveValue::Unchanged(Some(_)) => false,
-                }
-            };
-            // 处理非 Option 类型字段
-            ($field:expr, $non_option:ty) => {
-                matches!(&$field, sea_orm::ActiveValue::NotSet)
-            };
-        }
-    });
+    before_insert_body.push(should_fill_field_macro());
+
+    // 字段规范化，与 before_save 一致先于其他填充执行
+    before_insert_body.extend(generate_normalize_fills(fields, &quote! { active_model })?);
 
     // 生成插入时的字段填充逻辑
     if config.snowflake_id {
+        let assign_id = config.snowflake_id_assign(&quote! { generator }, &quote! { active_model });
         before_insert_body.push(quote! {
-            if should_fill_field!(active_model.id, String) {
-                if let Ok(id) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| generator.generate().to_string())) {
-                    active_model.id = sea_orm::ActiveValue::Set(id);
-                }
+            if should_fill_field!(active_model.#id, String) {
+                #assign_id
             }
         });
     }
 
-    if config.timestamps {
-        before_insert_body.push(quote! {
-            if should_fill_field!(active_model.create_time) {
-                active_model.create_time = sea_orm::ActiveValue::Set(Some(chrono::Utc::now().naive_utc()));
-            }
-            if should_fill_field!(active_model.update_time) {
-                active_model.update_time = sea_orm::ActiveValue::Set(Some(chrono::Utc::now().naive_utc()));
-            }
-        });
-    }
+    before_insert_body.extend(generate_managed_insert_fills(config, &quote! { active_model }));
 
-    if config.audit {
-        before_insert_body.push(quote! {
-            if should_fill_field!(active_model.create_by) {
-                if let Some(user_name) = &context.user_name {
-                    if !user_name.is_empty() {
-                        active_model.create_by = sea_orm::ActiveValue::Set(Some(user_name.clone()));
-                    }
-                }
-                if should_fill_field!(active_model.create_id) {
-                    if let Some(user_id) = &context.user_id {
-                        if !user_id.is_empty() {
-                            active_model.create_id = sea_orm::ActiveValue::Set(Some(user_id.clone()));
-                        }
-                    }
-                }
-            }
-        });
-    }
+    // 字段级默认值，先于排序、slug、哈希等派生列执行
+    before_insert_body.extend(generate_field_default_fills(fields, &quote! { active_model })?);
 
-    if config.tenant {
-        before_insert_body.push(quote! {
-            if should_fill_field!(active_model.tenant_id) {
-                if let Some(tenant_id) = &context.tenant_id {
-                    if !tenant_id.is_empty() {
-                        active_model.tenant_id = sea_orm::ActiveValue::Set(Some(tenant_id.clone()));
-                    }
-                }
-                if should_fill_field!(active_model.tenant_name) {
-                    if let Some(tenant_name) = &context.tenant_name {
-                        if !tenant_name.is_empty() {
-                            active_model.tenant_name = sea_orm::ActiveValue::Set(Some(tenant_name.clone()));
-                        }
-                    }
-                }
-            }
-        });
-    }
+    // 派生列同步
+    before_insert_body.extend(generate_sync_fills(config, fields, &quote! { active_model })?);
 
-    if config.version {
-        before_insert_body.push(quote! {
-            if should_fill_field!(active_model.version) {
-                active_model.version = sea_orm::ActiveValue::Set(Some(1));
-            }
-        });
-    }
+    // 排序列
+    before_insert_body.push(generate_sort_fill(config, fields, &quote! { active_model }, false)?);
 
-    if config.soft_delete {
-        before_insert_body.push(quote! {
-            if should_fill_field!(active_model.delete_flag) {
-                active_model.delete_flag = sea_orm::ActiveValue::Set(Some(0));
-            }
-        });
-    }
+    // slug
+    before_insert_body.push(generate_slug_fill(config, fields, &quote! { active_model }, false)?);
+
+    // 拼音检索列
+    before_insert_body.extend(generate_pinyin_fills(config, fields, &quote! { active_model })?);
+
+    // 行哈希
+    before_insert_body.push(generate_hash_fill(config, fields, &quote! { active_model }, true)?);
+
+    // 加密字段放在最后，保证其他填充逻辑读取到的是明文
+    before_insert_body.extend(generate_encrypt_fills(config, fields, &quote! { active_model }, false)?);
 
     let mut before_update_body = Vec::new();
     // 时间戳填充
-    if config.timestamps {
+    if config.fills_timestamps() {
+        let now_value = config.timestamp_value(now.clone());
         before_update_body.push(quote! {
             // 自动填充更新时间
             update_many = update_many.col_expr(
-                Self::Column::UpdateTime,
-                sea_orm::prelude::Expr::value(Some(chrono::Utc::now().naive_utc()))
+                Self::Column::#update_time_column,
+                sea_orm::prelude::Expr::value(Some(#now_value))
             );
         });
     }
 
     // 审计字段填充
     if config.audit {
+        let update_proxy_id_column = config.column("update_proxy_id");
+        let proxy = config.impersonation.then(|| quote! {
+            .col_expr(
+                Self::Column::#update_proxy_id_column,
+                sea_orm::prelude::Expr::value(context.acting_user_id.clone())
+            )
+        });
         before_update_body.push(quote! {
-            // 自动填充更新人信息
-            if let Some(user_name) = &context.user_name {
-                if !user_name.is_empty() {
-                    update_many = update_many.col_expr(
-                        Self::Column::UpdateBy,
-                        sea_orm::prelude::Expr::value(Some(user_name.clone()))
-                    );
-                }
-            }
-            if let Some(user_id) = &context.user_id {
-                if !user_id.is_empty() {
-                    update_many = update_many.col_expr(
-                        Self::Column::UpdateId,
-                        sea_orm::prelude::Expr::value(Some(user_id.clone()))
-                    );
+            // 自动填充更新人信息，姓名和 ID 都存在时才成对写入
+            if let (Some(user_name), Some(user_id)) = (&context.user_name, &context.user_id) {
+                if !user_name.is_empty() && !user_id.is_empty() {
+                    update_many = update_many
+                        .col_expr(
+                            Self::Column::#update_by_column,
+                            sea_orm::prelude::Expr::value(Some(user_name.clone()))
+                        )
+                        .col_expr(
+                            Self::Column::#update_id_column,
+                            sea_orm::prelude::Expr::value(Some(user_id.clone()))
+                        )
+                        #proxy;
                 }
             }
         });
@@ -619,50 +7821,123 @@ fn generate_soft_delete_ext(
         before_update_body.push(quote! {
             // 版本号自动递增
             update_many = update_many.col_expr(
-                Self::Column::Version,
-                sea_orm::prelude::Expr::col(Self::Column::Version).add(1)
+                Self::Column::#version_column,
+                sea_orm::prelude::Expr::col(Self::Column::#version_column).add(1)
             );
         });
     }
+    // 软删除同时写入的状态
+    let delete_state = delete_state_values(config, fields, false)?
+        .into_iter()
+        .map(|(ident, _, value)| quote! { active_model.#ident = sea_orm::ActiveValue::Set(#value); });
+    let generator_missing = config.message(Message::GeneratorMissing);
+    let batch_insert_prepared = config.message(Message::BatchInsertPrepared);
+    let versioned_soft_delete = generate_versioned_soft_delete(config, fields, active_model_name)?;
+
+    // 生成方法的文档，说明按当前配置实际执行的逻辑
+    let mut batch_update_columns = Vec::new();
+    if config.fills_timestamps() {
+        batch_update_columns.push("`update_time`");
+    }
+    if config.audit {
+        batch_update_columns.push("`update_by` / `update_id`（取自 AutoFieldContext，姓名和 ID 成对写入）");
+    }
+    if config.version {
+        batch_update_columns.push("`version = version + 1`");
+    }
+    let batch_update_doc = if batch_update_columns.is_empty() {
+        doc_attrs("没有需要维护的更新列，等同于 `Entity::update_many()`")
+    } else {
+        doc_attrs(&format!("返回预先写入更新约定的 `UpdateMany`：{}", batch_update_columns.join("、")))
+    };
+    let batch_insert_doc = doc_attrs(&format!(
+        "批量插入，逐条填充受管字段（{}）、字段默认值和派生列，整个批次共用同一时间戳\n\n需要注册雪花 ID 生成器，未注册时 panic",
+        config.insert_filled_columns().join("、")
+    ));
+    let state_doc = match (config.delete_time, config.delete_state.is_some()) {
+        (true, true) => "，写入 `delete_time` 并按 delete_sets_state 写入状态列",
+        (true, false) => "，写入 `delete_time`",
+        (false, true) => " 并按 delete_sets_state 写入状态列",
+        (false, false) => "",
+    };
+    let soft_delete_doc = doc_attrs(&format!(
+        "软删除：将 `delete_flag` 置为 {}{}，经过 before_save 刷新更新时间、更新人和版本号\n\n记录不存在或已删除时直接返回 Ok",
+        config.deleted_value(),
+        state_doc
+    ));
+    let soft_delete_many_doc = doc_attrs("逐条软删除，不保证原子性，需要原子性时使用 `soft_delete_many_atomic`");
+    let soft_delete_sql = generate_soft_delete_sql(config, fields)?;
+
+    // 配置了 ops_log 时统计实际删除的条数，全部成功后写入一行日志
+    let soft_delete_many_body = if config.ops_log.is_some() {
+        let log_call = config.ops_log_call("soft_delete_many", quote! { deleted }, quote! { Self::auto_field_ids_json(ids) });
+        quote! {
+            let mut deleted = 0;
+            for id in ids {
+                if matches!(Self::soft_delete_status(db, id).await?, __auto_field_trait::SoftDeleteStatus::Deleted) {
+                    deleted += 1;
+                }
+            }
+            #log_call
+            Ok(())
+        }
+    } else {
+        quote! {
+            for id in ids {
+                Self::soft_delete_status(db, id).await?;
+            }
+            Ok(())
+        }
+    };
+    let soft_delete_disabled_doc = doc_attrs("未启用 soft_delete，始终返回 `AutoFieldError::SoftDeleteDisabled`");
+
+    let soft_delete_disabled = auto_field_error(quote! {
+        SoftDeleteDisabled { table: sea_orm::EntityName::table_name(&Self::default()).to_string() }
+    });
     if !config.soft_delete {
         // 如果没有启用软删除，返回空实现
         return Ok(quote! {
             #[async_trait::async_trait]
-            impl ::auto_field_trait::auto_field_trait::CustomizationExt for #entity_name {
+            impl __auto_field_trait::CustomizationExt for #entity_name {
+                #soft_delete_disabled_doc
                 async fn soft_delete<C>(_db: &C, _id: &str) -> Result<(), sea_orm::DbErr>
                 where
                     C: sea_orm::ConnectionTrait,
                 {
-                    Err(sea_orm::DbErr::Custom("Soft delete not enabled for this entity".to_string()))
+                    Err(#soft_delete_disabled)
                 }
 
+                #soft_delete_disabled_doc
                 async fn soft_delete_many<C>(_db: &C, _ids: &[String]) -> Result<(), sea_orm::DbErr>
                 where
                     C: sea_orm::ConnectionTrait,
                 {
-                    Err(sea_orm::DbErr::Custom("Soft delete not enabled for this entity".to_string()))
+                    Err(#soft_delete_disabled)
                 }
 
+                #batch_update_doc
                 fn batch_update() -> sea_orm::UpdateMany<Self> {
                     // 获取当前上下文信息
-                    let context = ::auto_field_trait::auto_field_trait::AutoFieldContext::current_safe();
+                    #context_capture
                      // 调用原始的 update_many 方法
                     let mut update_many = sea_orm::EntityTrait::update_many();
                     #(#before_update_body)*
                     update_many
                 }
 
+                #batch_insert_doc
                 fn batch_insert_many<I>(models: I) -> sea_orm::Insert<Self::ActiveModel>
                 where
                     I: IntoIterator<Item = Self::ActiveModel>,
                 {
-                    use spring::plugin::ComponentRegistry;
                     // 获取当前上下文信息
-                    let context = ::auto_field_trait::auto_field_trait::AutoFieldContext::current_safe();
-                    let mut generator = match  spring::App::global().get_component::<snowflake::SnowflakeIdGenerator>(){
-                          Some(generator) => generator,
-                          None => panic!("No SnowflakeIdGenerator component found in the Spring application context."),
+                    #context_capture
+                    // 整个批次只加锁一次
+                    let mut generator = match Self::auto_field_id_generator() {
+                        Some(generator) => generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+                        None => panic!(#generator_missing),
                     };
+                    #now_capture
                     // 处理每个 ActiveModel，应用自动字段填充
                     let processed_models: Vec<Self::ActiveModel> = models
                         .into_iter()
@@ -672,7 +7947,7 @@ fn generate_soft_delete_ext(
                             active_model
                         })
                         .collect();
-                    log::debug!("准备执行批量插入，处理后共 {} 个ActiveModel", processed_models.len());
+                    log::debug!(#batch_insert_prepared, processed_models.len());
                     // 执行批量插入
                     let insert_builder = Self::insert_many(processed_models);
                     insert_builder
@@ -683,51 +7958,46 @@ fn generate_soft_delete_ext(
 
     Ok(quote! {
         #[async_trait::async_trait]
-        impl ::auto_field_trait::auto_field_trait::CustomizationExt for #entity_name {
+        impl __auto_field_trait::CustomizationExt for #entity_name {
+            #soft_delete_doc
             async fn soft_delete<C>(db: &C, id: &str) -> Result<(), sea_orm::DbErr>
             where
                 C: sea_orm::ConnectionTrait,
             {
-                if let Some(model) = Self::find_by_id(id).one(db).await? {
-                    let mut active_model: #active_model_name = model.into();
-
-                    // 设置删除标记为1，触发 before_update 钩子
-                    active_model.delete_flag = sea_orm::ActiveValue::Set(Some(1));
-                    active_model.update(db).await?;
-                }
-                Ok(())
+                Self::soft_delete_status(db, id).await.map(|_| ())
             }
 
+            #soft_delete_many_doc
             async fn soft_delete_many<C>(db: &C, ids: &[String]) -> Result<(), sea_orm::DbErr>
             where
                 C: sea_orm::ConnectionTrait,
             {
-                for id in ids {
-                    Self::soft_delete(db, id).await?;
-                }
-                Ok(())
+                #soft_delete_many_body
             }
 
+            #batch_update_doc
             fn batch_update() -> sea_orm::UpdateMany<Self> {
                 // 获取当前上下文信息
-                let context = ::auto_field_trait::auto_field_trait::AutoFieldContext::current_safe();
+                #context_capture
                  // 调用原始的 update_many 方法
                 let mut update_many = sea_orm::EntityTrait::update_many();
                 #(#before_update_body)*
                 update_many
             }
 
-           fn batch_insert_many<I>(models: I) -> sea_orm::Insert<Self::ActiveModel>
+            #batch_insert_doc
+            fn batch_insert_many<I>(models: I) -> sea_orm::Insert<Self::ActiveModel>
             where
                 I: IntoIterator<Item = Self::ActiveModel>,
             {
-                use spring::plugin::ComponentRegistry;
                 // 获取当前上下文信息
-                let context = ::auto_field_trait::auto_field_trait::AutoFieldContext::current_safe();
-                let mut generator = match  spring::App::global().get_component::<snowflake::SnowflakeIdGenerator>(){
-                      Some(generator) => generator,
-                      None => panic!("No SnowflakeIdGenerator component found in the Spring application context."),
+                #context_capture
+                // 整个批次只加锁一次
+                let mut generator = match Self::auto_field_id_generator() {
+                    Some(generator) => generator.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+                    None => panic!(#generator_missing),
                 };
+                #now_capture
                 // 处理每个 ActiveModel，应用自动字段填充
                 let processed_models: Vec<Self::ActiveModel> = models
                     .into_iter()
@@ -737,12 +8007,119 @@ fn generate_soft_delete_ext(
                         active_model
                     })
                     .collect();
-                log::debug!("准备执行批量插入，处理后共 {} 个ActiveModel", processed_models.len());
+                log::debug!(#batch_insert_prepared, processed_models.len());
                 // 执行批量插入
                 let insert_builder = Self::insert_many(processed_models);
                 insert_builder
             }
         }
+
+        impl #entity_name {
+            /// 软删除并返回删除结果。已删除的记录不再重复删除，避免再次递增版本号、改写更新人
+            pub async fn soft_delete_status<C>(
+                db: &C,
+                id: &str,
+            ) -> Result<__auto_field_trait::SoftDeleteStatus, sea_orm::DbErr>
+            where
+                C: sea_orm::ConnectionTrait,
+            {
+                use sea_orm::{ActiveModelTrait, EntityTrait};
+                let Some(model) = Self::find_by_id(id.to_owned()).one(db).await? else {
+                    return Ok(__auto_field_trait::SoftDeleteStatus::NotFound);
+                };
+                if #is_deleted {
+                    return Ok(__auto_field_trait::SoftDeleteStatus::AlreadyDeleted);
+                }
+
+                let mut active_model: #active_model_name = model.into();
+                // 设置删除标记为1，触发 before_update 钩子
+                active_model.#delete_flag = sea_orm::ActiveValue::Set(Some(#deleted));
+                #(#delete_state)*
+                active_model.update(db).await?;
+                Ok(__auto_field_trait::SoftDeleteStatus::Deleted)
+            }
+
+            #versioned_soft_delete
+
+            #soft_delete_sql
+
+            /// 在调用方提供的事务中批量软删除，任一记录失败时立即返回错误，由调用方回滚
+            pub async fn soft_delete_many_txn(
+                txn: &sea_orm::DatabaseTransaction,
+                ids: &[String],
+            ) -> Result<(), sea_orm::DbErr> {
+                let db = txn;
+                #soft_delete_many_body
+            }
+
+            /// 开启事务批量软删除，全部成功才提交，部分失败时整体回滚
+            pub async fn soft_delete_many_atomic<C>(db: &C, ids: &[String]) -> Result<(), sea_orm::DbErr>
+            where
+                C: sea_orm::TransactionTrait,
+            {
+                let txn = db.begin().await?;
+                Self::soft_delete_many_txn(&txn, ids).await?;
+                txn.commit().await
+            }
+
+            /// 按主键查询未删除的记录
+            pub fn find_by_id_not_deleted<T>(id: T) -> sea_orm::Select<Self>
+            where
+                T: Into<<<Self as sea_orm::EntityTrait>::PrimaryKey as sea_orm::PrimaryKeyTrait>::ValueType>,
+            {
+                Self::base_scope(<Self as sea_orm::EntityTrait>::find_by_id(id))
+            }
+        }
     })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: proc_macro2::TokenStream) -> AutoFieldConfig {
+        let attr: Attribute = syn::parse_quote! { #[auto_field(#args)] };
+        AutoFieldConfig::from_attributes(std::slice::from_ref(&attr)).unwrap()
+    }
+
+    #[test]
+    fn dependency_root_reads_the_manifest() {
+        assert_eq!(dependency_root("quote").unwrap().to_string(), ":: quote");
+        assert_eq!(dependency_root("proc-macro2").unwrap().to_string(), ":: proc_macro2");
+        assert!(dependency_root("auto_field_trait").is_none());
+    }
+
+    #[test]
+    fn sql_comment_prefixes_executed_statements() {
+        let statement = quote! { backend.build(&query) };
+        assert_eq!(config(quote! {}).sql_comment_statement("purge_expired", statement.clone()).to_string(), statement.to_string());
+
+        let commented = config(quote! { sql_comment }).sql_comment_statement("purge_expired", statement);
+        syn::parse2::<syn::Expr>(commented.clone()).unwrap();
+        let commented = commented.to_string();
+        assert!(commented.contains("statement . sql = format ! (\"{} {}\""), "{}", commented);
+        assert!(commented.contains("\"/* entity={} op={} */\""), "{}", commented);
+        assert!(!commented.contains("1 = 1"), "{}", commented);
+    }
+
+    #[test]
+    fn sql_comment_adds_predicate_to_returned_queries() {
+        let query = quote! { Self::find() };
+        assert_eq!(config(quote! {}).with_sql_comment("find_not_deleted", query.clone()).to_string(), query.to_string());
+
+        let commented = config(quote! { sql_comment }).with_sql_comment("find_not_deleted", query);
+        syn::parse2::<syn::Expr>(commented.clone()).unwrap();
+        let commented = commented.to_string();
+        assert!(commented.starts_with("sea_orm :: QueryFilter :: filter (Self :: find ()"), "{}", commented);
+        assert!(commented.contains("\"1 = 1 {}\""), "{}", commented);
+    }
+
+    #[test]
+    fn retention_requires_delete_time() {
+        let error = config(quote! { soft_delete, timestamps, retention = "30d" }).validate().unwrap_err();
+        assert!(error.to_string().contains("delete_time"), "{}", error);
+        assert!(config(quote! { delete_time }).validate().is_err());
+        config(quote! { soft_delete, delete_time, retention = "30d" }).validate().unwrap();
+    }
+}